@@ -0,0 +1,400 @@
+mod table;
+
+use chrono::{Local, NaiveDate};
+use clap::{Parser, Subcommand};
+use mnemona_core::db::{connect, is_encrypted, unlock_error};
+use mnemona_core::error::Error;
+use mnemona_core::types::{CourseArchive, CourseCode, CourseQuery, CourseStatus};
+use mnemona_core::{courses, exchange, paths, schedule, targets};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const PASSPHRASE_VAR: &str = "MNEMONA_PASSPHRASE";
+
+#[derive(Parser)]
+#[command(name = "mnemona", version, about = "Work with your Mnemona library from the terminal")]
+struct Cli {
+    /// Read the library from this directory instead of the app's
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+
+    /// Open this profile instead of the one open in the app
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Print JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the targets scheduled for the week containing a date
+    Schedule {
+        /// Any day of the week to show; defaults to today
+        #[arg(long)]
+        date: Option<NaiveDate>,
+
+        /// Only show targets with this tag; repeat for any of several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// List every course
+    Courses {
+        /// Only list courses with this tag; repeat for any of several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Show a course with its weeks and targets
+    Course {
+        /// A course code such as CS-241
+        code: CourseCode,
+    },
+    /// Mark a target as complete
+    Check {
+        /// A target such as CS-241:3.2, meaning target 2 of week 3
+        target: TargetRef,
+
+        /// Mark the target as incomplete instead
+        #[arg(long)]
+        undo: bool,
+    },
+    /// Activate a course and schedule its open weeks from this Monday, or
+    /// from the start of its term
+    Activate {
+        /// A course code such as CS-241
+        code: CourseCode,
+    },
+    /// Write courses to a JSON archive
+    Export {
+        /// The courses to export; defaults to all of them
+        codes: Vec<CourseCode>,
+
+        /// Write to this file instead of standard output
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Add the courses in a JSON archive to the library as drafts
+    Import {
+        /// The archive to read, or - for standard input
+        file: PathBuf,
+    },
+}
+
+/// A target written as its course, week serial and target serial, like
+/// `CS-241:3.2`.
+#[derive(Clone)]
+struct TargetRef {
+    course: CourseCode,
+    week: i64,
+    target: i64,
+}
+
+impl FromStr for TargetRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected a target like CS-241:3.2, got {:?}", s);
+
+        let (course, position) = s.split_once(':').ok_or_else(invalid)?;
+        let (week, target) = position.split_once('.').ok_or_else(invalid)?;
+
+        Ok(Self {
+            course: course.parse().map_err(|e: Error| e.to_string())?,
+            week: week.parse().map_err(|_| invalid())?,
+            target: target.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// The part of the profile's `settings.json` the CLI cares about.
+#[derive(Deserialize, Default)]
+struct Settings {
+    #[serde(default)]
+    weekly_capacity_minutes: Option<i64>,
+}
+
+fn load_settings(profile_dir: &Path) -> Result<Settings, Error> {
+    let path = profile_dir.join("settings.json");
+
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Takes the passphrase of an encrypted library from the environment, or
+/// asks for it.
+fn read_passphrase() -> Result<String, Error> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_VAR) {
+        return Ok(passphrase);
+    }
+
+    eprint!("Passphrase: ");
+    io::stderr().flush()?;
+
+    let mut passphrase = String::new();
+    io::stdin().lock().read_line(&mut passphrase)?;
+
+    Ok(passphrase.trim_end_matches(['\r', '\n']).to_string())
+}
+
+async fn open(cli: &Cli) -> Result<(SqlitePool, PathBuf), Error> {
+    let root = paths::resolve(cli.data_dir.clone(), &paths::default_dir()?)?.path;
+    let profile_dir = paths::find_profile_dir(&root, cli.profile.as_deref())?;
+    let path = paths::database_path(&profile_dir);
+
+    // Unlike the app, the CLI never creates a library.
+    if !path.exists() {
+        return Err(Error::NotFound(format!("No library found at {}", path.display())));
+    }
+
+    let passphrase = if is_encrypted(&path) {
+        Some(read_passphrase()?)
+    } else {
+        None
+    };
+
+    let pool = connect(&path, passphrase.as_deref())
+        .await
+        .map_err(unlock_error)?;
+
+    Ok((pool, profile_dir))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), Error> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn check_mark(is_complete: bool) -> String {
+    if is_complete { "x" } else { " " }.to_string()
+}
+
+fn status_label(status: &CourseStatus) -> String {
+    match status {
+        CourseStatus::Draft => "draft",
+        CourseStatus::Inactive => "inactive",
+        CourseStatus::Active => "active",
+        CourseStatus::Complete => "complete",
+    }
+    .to_string()
+}
+
+async fn show_schedule(
+    cli: &Cli,
+    pool: &SqlitePool,
+    date: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<(), Error> {
+    let date = date.unwrap_or_else(|| Local::now().date_naive());
+    let monday = schedule::monday_of(date);
+
+    let items = schedule::get_schedule(pool, monday, tags.to_vec()).await?;
+
+    if cli.json {
+        return print_json(&items);
+    }
+
+    println!("Week of {}\n", monday);
+
+    let mut rows = Vec::new();
+
+    for item in &items {
+        for week in &item.weeks {
+            for target in &week.targets {
+                rows.push(vec![
+                    check_mark(target.is_complete),
+                    format!("{}-{}:{}.{}", item.course.department, item.course.serial, week.serial, target.serial),
+                    item.course.name.clone(),
+                    target.text.clone(),
+                ]);
+            }
+        }
+    }
+
+    table::print(&["DONE", "TARGET", "COURSE", "TEXT"], &rows);
+
+    Ok(())
+}
+
+async fn show_courses(cli: &Cli, pool: &SqlitePool, tags: &[String]) -> Result<(), Error> {
+    let query = CourseQuery {
+        tags: tags.to_vec(),
+        ..CourseQuery::default()
+    };
+
+    let courses = courses::get_courses(pool, query).await?.courses;
+
+    if cli.json {
+        return print_json(&courses);
+    }
+
+    let rows: Vec<Vec<String>> = courses
+        .iter()
+        .map(|course| {
+            vec![
+                format!("{}-{}", course.department, course.serial),
+                course.name.clone(),
+                status_label(&course.status),
+                format!("{}/{}", course.weeks.num_complete, course.weeks.num_total),
+            ]
+        })
+        .collect();
+
+    table::print(&["CODE", "NAME", "STATUS", "WEEKS"], &rows);
+
+    Ok(())
+}
+
+async fn show_course(cli: &Cli, pool: &SqlitePool, code: &CourseCode) -> Result<(), Error> {
+    let course_id = courses::find_course_id(pool, code).await?;
+    let course = courses::get_course(pool, course_id).await?;
+
+    if cli.json {
+        return print_json(&course);
+    }
+
+    println!("{}-{} {} ({})", course.department.code, course.serial, course.name, status_label(&course.status));
+
+    if let Some(description) = &course.description {
+        println!("{}", description);
+    }
+
+    println!();
+
+    let mut rows = Vec::new();
+
+    for week in &course.weeks {
+        rows.push(vec![
+            check_mark(week.is_complete),
+            week.serial.to_string(),
+            week.date.map(|d| d.to_string()).unwrap_or_default(),
+            week.text.clone(),
+        ]);
+
+        for target in &week.targets {
+            rows.push(vec![
+                check_mark(target.is_complete),
+                format!("{}.{}", week.serial, target.serial),
+                String::new(),
+                format!("  {}", target.text),
+            ]);
+        }
+    }
+
+    table::print(&["DONE", "#", "DATE", "TEXT"], &rows);
+
+    Ok(())
+}
+
+async fn check(pool: &SqlitePool, target: &TargetRef, undo: bool) -> Result<(), Error> {
+    let course_id = courses::find_course_id(pool, &target.course).await?;
+    let target_id = targets::find_target_id(pool, &course_id, target.week, target.target).await?;
+
+    targets::change_target_status(pool, target_id, !undo).await
+}
+
+async fn activate(pool: &SqlitePool, profile_dir: &Path, code: &CourseCode) -> Result<(), Error> {
+    let course_id = courses::find_course_id(pool, code).await?;
+    let capacity = load_settings(profile_dir)?.weekly_capacity_minutes;
+
+    let overflow = courses::update_course_status(pool, course_id, "active".to_string(), capacity).await?;
+
+    if let Some(overflow) = overflow {
+        eprintln!(
+            "mnemona: {} weeks do not fit in {}, which ends {}; the last is scheduled for {}",
+            overflow.num_weeks, overflow.term, overflow.end_date, overflow.last_date
+        );
+    }
+
+    Ok(())
+}
+
+async fn export(pool: &SqlitePool, codes: &[CourseCode], output: Option<&Path>) -> Result<(), Error> {
+    let course_ids = if codes.is_empty() {
+        None
+    } else {
+        let mut ids = Vec::new();
+        for code in codes {
+            ids.push(courses::find_course_id(pool, code).await?);
+        }
+        Some(ids)
+    };
+
+    let archive = exchange::export_courses(pool, course_ids).await?;
+    let contents = serde_json::to_string_pretty(&archive)?;
+
+    match output {
+        Some(path) => fs::write(path, contents)?,
+        None => println!("{}", contents),
+    }
+
+    Ok(())
+}
+
+async fn import(cli: &Cli, pool: &SqlitePool, file: &Path) -> Result<(), Error> {
+    let contents = if file == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents)?;
+        contents
+    } else {
+        fs::read_to_string(file)?
+    };
+
+    let archive: CourseArchive = serde_json::from_str(&contents)?;
+    let imported = exchange::import_courses(pool, archive).await?;
+
+    if cli.json {
+        return print_json(&imported);
+    }
+
+    let rows: Vec<Vec<String>> = imported
+        .iter()
+        .map(|course| vec![format!("{}-{}", course.department, course.serial), course.name.clone()])
+        .collect();
+
+    table::print(&["CODE", "NAME"], &rows);
+
+    Ok(())
+}
+
+async fn run(cli: &Cli) -> Result<(), Error> {
+    let (pool, profile_dir) = open(cli).await?;
+
+    let result = match &cli.command {
+        Command::Schedule { date, tags } => show_schedule(cli, &pool, *date, tags).await,
+        Command::Courses { tags } => show_courses(cli, &pool, tags).await,
+        Command::Course { code } => show_course(cli, &pool, code).await,
+        Command::Check { target, undo } => check(&pool, target, *undo).await,
+        Command::Activate { code } => activate(&pool, &profile_dir, code).await,
+        Command::Export { codes, output } => export(&pool, codes, output.as_deref()).await,
+        Command::Import { file } => import(cli, &pool, file).await,
+    };
+
+    pool.close().await;
+
+    result
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(&cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("mnemona: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,27 @@
+/// Prints `rows` as left-aligned columns under `headers`, sized to the
+/// widest cell in each column.
+pub fn print(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+
+        println!("{}", padded.join("  ").trim_end());
+    };
+
+    line(headers.to_vec());
+
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
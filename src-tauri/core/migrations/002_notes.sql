@@ -0,0 +1,38 @@
+CREATE TABLE IF NOT EXISTS notes (
+    id TEXT PRIMARY KEY NOT NULL,
+    course_id TEXT,
+    week_id TEXT,
+    target_id TEXT,
+    body TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    CHECK (
+        (course_id IS NOT NULL) + (week_id IS NOT NULL) + (target_id IS NOT NULL) = 1
+    ),
+    FOREIGN KEY (course_id) REFERENCES courses (id) ON DELETE CASCADE,
+    FOREIGN KEY (week_id) REFERENCES weeks (id) ON DELETE CASCADE,
+    FOREIGN KEY (target_id) REFERENCES targets (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notes_course ON notes(course_id);
+CREATE INDEX IF NOT EXISTS idx_notes_week ON notes(week_id);
+CREATE INDEX IF NOT EXISTS idx_notes_target ON notes(target_id);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+    body,
+    content = 'notes',
+    content_rowid = 'rowid'
+);
+
+CREATE TRIGGER IF NOT EXISTS notes_ai AFTER INSERT ON notes BEGIN
+    INSERT INTO notes_fts (rowid, body) VALUES (new.rowid, new.body);
+END;
+
+CREATE TRIGGER IF NOT EXISTS notes_ad AFTER DELETE ON notes BEGIN
+    INSERT INTO notes_fts (notes_fts, rowid, body) VALUES ('delete', old.rowid, old.body);
+END;
+
+CREATE TRIGGER IF NOT EXISTS notes_au AFTER UPDATE OF body ON notes BEGIN
+    INSERT INTO notes_fts (notes_fts, rowid, body) VALUES ('delete', old.rowid, old.body);
+    INSERT INTO notes_fts (rowid, body) VALUES (new.rowid, new.body);
+END;
@@ -0,0 +1,70 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    kind UNINDEXED,
+    item_id UNINDEXED,
+    course_id UNINDEXED,
+    name,
+    description,
+    book,
+    prompt,
+    text,
+    source
+);
+
+INSERT INTO search_index (kind, item_id, course_id, name, description, book, prompt)
+SELECT 'course', id, id, name, description, book, prompt FROM courses;
+
+INSERT INTO search_index (kind, item_id, course_id, text)
+SELECT 'week', id, course_id, text FROM weeks;
+
+INSERT INTO search_index (kind, item_id, course_id, text, source)
+SELECT 'target', t.id, w.course_id, t.text, t.source
+FROM targets t
+JOIN weeks w ON t.week_id = w.id;
+
+CREATE TRIGGER IF NOT EXISTS courses_search_ai AFTER INSERT ON courses BEGIN
+    INSERT INTO search_index (kind, item_id, course_id, name, description, book, prompt)
+    VALUES ('course', new.id, new.id, new.name, new.description, new.book, new.prompt);
+END;
+
+CREATE TRIGGER IF NOT EXISTS courses_search_au
+AFTER UPDATE OF name, description, book, prompt ON courses BEGIN
+    DELETE FROM search_index WHERE kind = 'course' AND item_id = old.id;
+    INSERT INTO search_index (kind, item_id, course_id, name, description, book, prompt)
+    VALUES ('course', new.id, new.id, new.name, new.description, new.book, new.prompt);
+END;
+
+CREATE TRIGGER IF NOT EXISTS courses_search_ad AFTER DELETE ON courses BEGIN
+    DELETE FROM search_index WHERE kind = 'course' AND item_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS weeks_search_ai AFTER INSERT ON weeks BEGIN
+    INSERT INTO search_index (kind, item_id, course_id, text)
+    VALUES ('week', new.id, new.course_id, new.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS weeks_search_au AFTER UPDATE OF text ON weeks BEGIN
+    DELETE FROM search_index WHERE kind = 'week' AND item_id = old.id;
+    INSERT INTO search_index (kind, item_id, course_id, text)
+    VALUES ('week', new.id, new.course_id, new.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS weeks_search_ad AFTER DELETE ON weeks BEGIN
+    DELETE FROM search_index WHERE kind = 'week' AND item_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS targets_search_ai AFTER INSERT ON targets BEGIN
+    INSERT INTO search_index (kind, item_id, course_id, text, source)
+    SELECT 'target', new.id, course_id, new.text, new.source
+    FROM weeks WHERE id = new.week_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS targets_search_au AFTER UPDATE OF text, source ON targets BEGIN
+    DELETE FROM search_index WHERE kind = 'target' AND item_id = old.id;
+    INSERT INTO search_index (kind, item_id, course_id, text, source)
+    SELECT 'target', new.id, course_id, new.text, new.source
+    FROM weeks WHERE id = new.week_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS targets_search_ad AFTER DELETE ON targets BEGIN
+    DELETE FROM search_index WHERE kind = 'target' AND item_id = old.id;
+END;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS study_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    target_id TEXT NOT NULL,
+    started_at DATETIME NOT NULL,
+    ended_at DATETIME,
+    duration_seconds INTEGER,
+    FOREIGN KEY (target_id) REFERENCES targets (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_study_sessions_target ON study_sessions(target_id);
@@ -0,0 +1 @@
+ALTER TABLE targets ADD COLUMN estimated_minutes INTEGER;
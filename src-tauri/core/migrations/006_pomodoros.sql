@@ -0,0 +1 @@
+ALTER TABLE study_sessions ADD COLUMN is_pomodoro BOOLEAN NOT NULL DEFAULT FALSE;
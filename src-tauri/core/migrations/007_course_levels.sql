@@ -0,0 +1 @@
+ALTER TABLE courses ADD COLUMN level TEXT;
@@ -0,0 +1,38 @@
+-- Give every duplicate course serial after the first the next free number
+-- past the department's maximum, so the unique index below can be created.
+-- The new serials are worked out before any row changes, since the maximum
+-- would otherwise grow with every row updated.
+WITH duplicates AS MATERIALIZED (
+    SELECT
+        id,
+        (SELECT MAX(m.serial) FROM courses m WHERE m.department_id = c.department_id)
+            + ROW_NUMBER() OVER (PARTITION BY department_id ORDER BY serial, rowid) AS serial
+    FROM courses c
+    WHERE rowid NOT IN (
+        SELECT MIN(rowid) FROM courses GROUP BY department_id, serial
+    )
+)
+UPDATE courses
+SET serial = duplicates.serial
+FROM duplicates
+WHERE duplicates.id = courses.id;
+
+-- Duplicate week serials move past the course's last week in the same way,
+-- leaving every other week where it is.
+WITH duplicates AS MATERIALIZED (
+    SELECT
+        id,
+        (SELECT MAX(m.serial) FROM weeks m WHERE m.course_id = w.course_id)
+            + ROW_NUMBER() OVER (PARTITION BY course_id ORDER BY serial, rowid) AS serial
+    FROM weeks w
+    WHERE rowid NOT IN (
+        SELECT MIN(rowid) FROM weeks GROUP BY course_id, serial
+    )
+)
+UPDATE weeks
+SET serial = duplicates.serial
+FROM duplicates
+WHERE duplicates.id = weeks.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_courses_department_serial ON courses(department_id, serial);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_weeks_course_serial ON weeks(course_id, serial);
@@ -0,0 +1,3 @@
+ALTER TABLE courses ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_courses_deleted ON courses(deleted_at);
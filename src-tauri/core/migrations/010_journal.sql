@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS journal (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    label TEXT NOT NULL,
+    undo TEXT NOT NULL,
+    redo TEXT NOT NULL,
+    is_undone BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS course_versions (
+    id TEXT PRIMARY KEY,
+    course_id TEXT NOT NULL,
+    origin TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    FOREIGN KEY (course_id) REFERENCES courses(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_course_versions_course ON course_versions(course_id, created_at);
@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    color TEXT NOT NULL
+);
+
+-- Each row tags exactly one course, week or target.
+CREATE TABLE IF NOT EXISTS taggings (
+    tag_id TEXT NOT NULL,
+    course_id TEXT,
+    week_id TEXT,
+    target_id TEXT,
+    CHECK (
+        (course_id IS NOT NULL) + (week_id IS NOT NULL) + (target_id IS NOT NULL) = 1
+    ),
+    FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE,
+    FOREIGN KEY (course_id) REFERENCES courses (id) ON DELETE CASCADE,
+    FOREIGN KEY (week_id) REFERENCES weeks (id) ON DELETE CASCADE,
+    FOREIGN KEY (target_id) REFERENCES targets (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_taggings_course ON taggings(course_id, tag_id) WHERE course_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_taggings_week ON taggings(week_id, tag_id) WHERE week_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_taggings_target ON taggings(target_id, tag_id) WHERE target_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_taggings_tag ON taggings(tag_id);
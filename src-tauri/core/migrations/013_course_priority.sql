@@ -0,0 +1,5 @@
+-- Higher priorities come first in the schedule and claim weekly capacity
+-- before lower ones when a course is activated.
+ALTER TABLE courses ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_courses_priority ON courses(priority);
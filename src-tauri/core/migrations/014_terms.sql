@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS terms (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    CHECK (start_date <= end_date)
+);
+
+-- The Mondays of the weeks in a term with no study scheduled.
+CREATE TABLE IF NOT EXISTS term_breaks (
+    term_id TEXT NOT NULL,
+    date DATE NOT NULL,
+    PRIMARY KEY (term_id, date),
+    FOREIGN KEY (term_id) REFERENCES terms (id) ON DELETE CASCADE
+);
+
+ALTER TABLE courses ADD COLUMN term_id TEXT REFERENCES terms (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_courses_term ON courses(term_id);
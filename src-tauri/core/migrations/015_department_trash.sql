@@ -0,0 +1,3 @@
+-- A department deleted along with its courses waits here until the last of
+-- those courses is purged from the trash.
+ALTER TABLE departments ADD COLUMN deleted_at DATETIME;
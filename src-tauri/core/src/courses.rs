@@ -0,0 +1,1631 @@
+use chrono::{Duration, Local, NaiveDate, Utc};
+use sqlx::{SqlitePool, Row};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::journal::{record, snapshot_content, snapshot_status, Operation};
+use crate::schedule::monday_of;
+use crate::terms::get_course_term;
+use crate::versions::{save_original_version, save_version};
+use crate::error::Error;
+use crate::types::{ContentOrigin, Course, CourseCode, CourseContentDraft, CourseHeader, CourseDraft, CourseLevel, CoursePage, CoursePreview, CourseQuery, CourseSort, Department, DepartmentDraft, Target, TargetsPreview, TermOverflow, Week, WeeksPreview};
+
+impl CourseLevel {
+    /// The inclusive range of serials reserved for courses of this level.
+    pub(crate) fn band(&self) -> (i64, i64) {
+        match self {
+            CourseLevel::Intro => (100, 199),
+            CourseLevel::Intermediate => (200, 299),
+            CourseLevel::Advanced => (300, 499),
+            CourseLevel::Graduate => (500, 599)
+        }
+    }
+
+    pub(crate) fn from_serial(serial: i64) -> Option<Self> {
+        match serial {
+            100..=199 => Some(CourseLevel::Intro),
+            200..=299 => Some(CourseLevel::Intermediate),
+            300..=499 => Some(CourseLevel::Advanced),
+            500..=599 => Some(CourseLevel::Graduate),
+            _ => None
+        }
+    }
+}
+
+impl FromStr for CourseCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        let department = s[..split].trim_end_matches(['-', ' ']);
+
+        match s[split..].parse() {
+            Ok(serial) if !department.is_empty() => Ok(CourseCode {
+                department: department.to_string(),
+                serial
+            }),
+            _ => Err(Error::Validation(format!("Expected a course code like CS-241, got {:?}", s)))
+        }
+    }
+}
+
+impl fmt::Display for CourseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.department, self.serial)
+    }
+}
+
+/// The serials a user can pin a course to: anything in the level bands.
+/// Larger serials are only handed out once the bands are full, and zero or
+/// negative ones are reserved for renumbering.
+const PINNABLE_SERIALS: (i64, i64) = (100, 599);
+
+/// Checks that a user-chosen `serial` is in the level bands and not taken by
+/// another course in the department.
+pub(crate) async fn ensure_serial_free(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    department_id: &str,
+    serial: i64,
+    except_course_id: Option<&str>,
+) -> Result<(), Error> {
+    let (lower, upper) = PINNABLE_SERIALS;
+
+    if !(lower..=upper).contains(&serial) {
+        return Err(Error::Validation(format!(
+            "Course numbers must be between {} and {}, got {}",
+            lower, upper, serial
+        )));
+    }
+
+    let taken: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM courses
+         WHERE department_id = ? AND serial = ?"
+    )
+    .bind(department_id)
+    .bind(serial)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match taken {
+        Some(id) if Some(id.as_str()) != except_course_id => {
+            Err(Error::Conflict(format!("Serial {} is already taken in this department", serial)))
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn pick_serial_in_band(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    department_id: &str,
+    (lower, upper): (i64, i64),
+) -> Result<Option<i64>, sqlx::Error> {
+    let taken: Vec<i64> = sqlx::query_scalar(
+        "SELECT serial FROM courses
+         WHERE department_id = ? AND serial BETWEEN ? AND ?"
+    )
+    .bind(department_id)
+    .bind(lower)
+    .bind(upper)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let free: Vec<i64> = (lower..=upper)
+        .filter(|serial| !taken.contains(serial))
+        .collect();
+
+    if free.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(free[(rand::random::<u64>() % free.len() as u64) as usize]))
+}
+
+/// Picks an unused serial in the department. With a level the serial comes
+/// from that level's band; otherwise, or once the band is full, any free
+/// 3-digit serial is used, growing to more digits as ranges fill up.
+pub(crate) async fn generate_course_serial(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    department_id: &str,
+    level: Option<CourseLevel>,
+) -> Result<i64, sqlx::Error> {
+    if let Some(level) = level {
+        if let Some(serial) = pick_serial_in_band(tx, department_id, level.band()).await? {
+            return Ok(serial);
+        }
+    }
+
+    let mut digits = 3;
+
+    loop {
+        let lower = 10_i64.pow(digits - 1);
+        let upper = 10_i64.pow(digits) - 1;
+        let range_size = upper - lower + 1;
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM courses
+             WHERE department_id = ? AND serial BETWEEN ? AND ?"
+        )
+        .bind(department_id)
+        .bind(lower)
+        .bind(upper)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        if count < range_size {
+            for _ in 0..10 {
+                let candidate =
+                    lower + (rand::random::<u64>() % range_size as u64) as i64;
+
+                let exists: Option<i64> = sqlx::query_scalar(
+                    "SELECT 1 FROM courses
+                     WHERE department_id = ? AND serial = ?"
+                )
+                .bind(department_id)
+                .bind(candidate)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+                if exists.is_none() {
+                    return Ok(candidate);
+                }
+            }
+        }
+
+        digits += 1;
+    }
+}
+
+/// Assigns a Monday to each week in order. Without a capacity every week gets
+/// its own calendar week; with one, light weeks share a calendar week and
+/// heavy weeks push the following ones back. Weeks without an estimate are
+/// treated as filling a whole calendar week. `committed` holds the minutes
+/// other courses already take up in each calendar week, which are filled
+/// around, and nothing is scheduled in the `breaks`.
+fn plan_week_dates(
+    start: NaiveDate,
+    estimates: &[Option<i64>],
+    capacity: Option<i64>,
+    committed: &HashMap<NaiveDate, i64>,
+    breaks: &HashSet<NaiveDate>,
+) -> Vec<NaiveDate> {
+    let Some(capacity) = capacity.filter(|c| *c > 0) else {
+        return start
+            .iter_weeks()
+            .filter(|date| !breaks.contains(date))
+            .take(estimates.len())
+            .collect();
+    };
+
+    let load = |date: &NaiveDate| {
+        if breaks.contains(date) {
+            capacity
+        } else {
+            committed.get(date).copied().unwrap_or(0).max(0)
+        }
+    };
+
+    let mut dates = Vec::with_capacity(estimates.len());
+    let mut date = start;
+    let mut used = load(&date);
+
+    for estimate in estimates {
+        let minutes = estimate.unwrap_or(capacity).max(0);
+
+        while used >= capacity || (used > 0 && used + minutes > capacity) {
+            date += Duration::weeks(1);
+            used = load(&date);
+        }
+
+        dates.push(date);
+        used += minutes;
+
+        while used >= capacity {
+            used -= capacity;
+            date += Duration::weeks(1);
+            used += load(&date);
+        }
+    }
+
+    dates
+}
+
+/// The minutes per calendar week, from `start` on, taken up by the open
+/// weeks of active courses with a higher priority than `course_id`. Weeks
+/// without an estimate count as filling their calendar week.
+async fn committed_minutes(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    course_id: &str,
+    start: NaiveDate,
+    capacity: i64,
+) -> Result<HashMap<NaiveDate, i64>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT w.date, SUM(t.estimated_minutes) AS estimated_minutes
+        FROM weeks w
+        JOIN courses c ON w.course_id = c.id
+        LEFT JOIN targets t ON t.week_id = w.id
+        WHERE c.id != ?
+          AND c.status = 'active'
+          AND c.deleted_at IS NULL
+          AND c.priority > (SELECT priority FROM courses WHERE id = ?)
+          AND w.is_complete = FALSE
+          AND w.date >= ?
+        GROUP BY w.id
+        "#
+    )
+    .bind(course_id)
+    .bind(course_id)
+    .bind(start)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let mut committed = HashMap::new();
+
+    for row in rows {
+        let minutes: Option<i64> = row.get("estimated_minutes");
+        *committed.entry(row.get("date")).or_insert(0) += minutes.unwrap_or(capacity);
+    }
+
+    Ok(committed)
+}
+
+pub(crate) fn validate_content(draft: &CourseContentDraft) -> Result<(), Error> {
+    let mut serials: Vec<i64> = draft.weeks.iter().map(|w| w.serial).collect();
+    serials.sort_unstable();
+    serials.dedup();
+    if serials.len() != draft.weeks.len() {
+        return Err(Error::Validation("Week serials must be unique".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Replaces the name, description, book, weeks and targets of a course with
+/// `draft`. Weeks and targets are matched by serial and updated in place, so
+/// the study sessions, notes and tags hanging off them survive; only the ones
+/// whose serial is gone are deleted. With `keep_progress`, weeks keep their
+/// date when their serial survives and their completion when their text is
+/// unchanged, and targets keep their completion when the same text is still
+/// in the same week.
+pub(crate) async fn write_content(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    course_id: &str,
+    draft: &CourseContentDraft,
+    keep_progress: bool,
+) -> Result<(), sqlx::Error> {
+    let mut existing_weeks: HashMap<i64, (String, String, Option<NaiveDate>, bool)> = HashMap::new();
+    let mut existing_targets: HashMap<(i64, i64), String> = HashMap::new();
+    let mut target_progress: HashMap<(i64, String), bool> = HashMap::new();
+
+    let rows = sqlx::query(
+        "SELECT id, serial, text, date, is_complete FROM weeks WHERE course_id = ?"
+    )
+    .bind(course_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        existing_weeks.insert(
+            row.get("serial"),
+            (row.get("id"), row.get("text"), row.get("date"), row.get("is_complete"))
+        );
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT t.id, w.serial AS week_serial, t.serial, t.text, t.is_complete
+        FROM targets t
+        JOIN weeks w ON t.week_id = w.id
+        WHERE w.course_id = ?
+        "#
+    )
+    .bind(course_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        let week_serial: i64 = row.get("week_serial");
+
+        existing_targets.insert((week_serial, row.get("serial")), row.get("id"));
+        target_progress.insert((week_serial, row.get("text")), row.get("is_complete"));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE courses
+        SET name = ?, description = ?, book = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(&draft.name)
+    .bind(&draft.description)
+    .bind(&draft.book)
+    .bind(course_id)
+    .execute(&mut **tx)
+    .await?;
+
+    for week in &draft.weeks {
+        let existing = existing_weeks.remove(&week.serial);
+
+        let (date, is_complete) = match &existing {
+            Some((_, text, date, is_complete)) if keep_progress => {
+                (*date, *is_complete && *text == week.text)
+            }
+            _ => (None, false),
+        };
+
+        let week_id = match existing {
+            Some((week_id, ..)) => {
+                sqlx::query("UPDATE weeks SET text = ?, date = ?, is_complete = ? WHERE id = ?")
+                    .bind(&week.text)
+                    .bind(date)
+                    .bind(is_complete)
+                    .bind(&week_id)
+                    .execute(&mut **tx)
+                    .await?;
+
+                week_id
+            }
+            None => {
+                let week_id = uuid::Uuid::new_v4().to_string();
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO weeks (id, course_id, serial, text, date, is_complete)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    "#
+                )
+                .bind(&week_id)
+                .bind(course_id)
+                .bind(week.serial)
+                .bind(&week.text)
+                .bind(date)
+                .bind(is_complete)
+                .execute(&mut **tx)
+                .await?;
+
+                week_id
+            }
+        };
+
+        for target in &week.targets {
+            let is_complete = keep_progress
+                && target_progress
+                    .get(&(week.serial, target.text.clone()))
+                    .copied()
+                    .unwrap_or(false);
+
+            match existing_targets.remove(&(week.serial, target.serial)) {
+                Some(target_id) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE targets
+                        SET text = ?, source = ?, is_complete = ?, estimated_minutes = ?
+                        WHERE id = ?
+                        "#
+                    )
+                    .bind(&target.text)
+                    .bind(&target.source)
+                    .bind(is_complete)
+                    .bind(target.estimated_minutes)
+                    .bind(target_id)
+                    .execute(&mut **tx)
+                    .await?;
+                }
+                None => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO targets
+                        (id, week_id, serial, text, source, is_complete, estimated_minutes)
+                        VALUES (?, ?, ?, ?, ?, ?, ?)
+                        "#
+                    )
+                    .bind(uuid::Uuid::new_v4().to_string())
+                    .bind(&week_id)
+                    .bind(target.serial)
+                    .bind(&target.text)
+                    .bind(&target.source)
+                    .bind(is_complete)
+                    .bind(target.estimated_minutes)
+                    .execute(&mut **tx)
+                    .await?;
+                }
+            }
+        }
+    }
+
+    // Whatever was not matched above was removed from the course.
+    for target_id in existing_targets.into_values() {
+        sqlx::query("DELETE FROM targets WHERE id = ?")
+            .bind(target_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    for (week_id, ..) in existing_weeks.into_values() {
+        sqlx::query("DELETE FROM weeks WHERE id = ?")
+            .bind(week_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn create_courses(
+    pool: &SqlitePool,
+    courses: Vec<CourseDraft>,
+    departments: Vec<DepartmentDraft>,
+) -> Result<(), Error> {
+    let mut tx = pool
+        .begin()
+        .await?;
+
+    for dept in departments {
+        let existing = sqlx::query(
+            "SELECT id FROM departments WHERE code = ?"
+        )
+        .bind(&dept.code)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        match existing {
+            Some(row) => {
+                let id: String = row.get("id");
+
+                // A department waiting for its trashed courses to be purged
+                // is back in use.
+                sqlx::query(
+                    "UPDATE departments SET name = ?, deleted_at = NULL WHERE id = ?"
+                )
+                .bind(&dept.name)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                let id = Uuid::new_v4().to_string();
+
+                sqlx::query(
+                    "INSERT INTO departments (id, code, name)
+                     VALUES (?, ?, ?)"
+                )
+                .bind(id)
+                .bind(&dept.code)
+                .bind(&dept.name)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, code FROM departments"
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut dept_map: HashMap<String, String> = HashMap::new();
+    let mut course_ids = Vec::new();
+
+    for row in rows {
+        let id: String = row.get("id");
+        let code: String = row.get("code");
+        dept_map.insert(code, id);
+    }
+
+    for course in courses {
+        let department_id = dept_map
+            .get(&course.department)
+            .ok_or_else(|| Error::NotFound(format!("Unknown department code: {}", course.department)))?
+            .clone();
+
+        let serial = match course.serial {
+            Some(serial) => {
+                ensure_serial_free(&mut tx, &department_id, serial, None).await?;
+                serial
+            }
+            None => generate_course_serial(&mut tx, &department_id, course.level).await?,
+        };
+
+        let id = Uuid::new_v4().to_string();
+        course_ids.push(id.clone());
+
+        sqlx::query(
+            "INSERT INTO courses
+            (id, department_id, serial, name, description, book, prompt, level)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(id)
+        .bind(department_id)
+        .bind(serial)
+        .bind(course.name)
+        .bind(course.description)
+        .bind(course.book)
+        .bind(course.prompt)
+        .bind(course.level)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Undoing a creation moves the new courses to the trash; departments
+    // created or renamed along the way are kept.
+    record(
+        &mut tx,
+        "Create courses",
+        Operation::Deleted {
+            course_ids: course_ids.clone(),
+            deleted_at: Some(Utc::now()),
+        },
+        Operation::Deleted {
+            course_ids,
+            deleted_at: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_course(
+    pool: &SqlitePool,
+    course_id: String,
+) -> Result<Course, Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT
+          c.id            AS course_id,
+          c.serial        AS course_serial,
+          c.name          AS course_name,
+          c.description   AS course_description,
+          c.book          AS course_book,
+          c.prompt        AS course_prompt,
+          c.level         AS course_level,
+          c.status        AS course_status,
+          c.priority      AS course_priority,
+          c.term_id       AS course_term_id,
+          d.id            AS dept_id,
+          d.code          AS dept_code,
+          d.name          AS dept_name
+        FROM courses c
+        JOIN departments d ON c.department_id = d.id
+        WHERE c.id = ? AND c.deleted_at IS NULL
+        "#
+    )
+    .bind(&course_id)
+    .fetch_one(pool)
+    .await?;
+
+    let mut course = Course {
+        id: row.get("course_id"),
+        department: Department {
+            id: row.get("dept_id"),
+            code: row.get("dept_code"),
+            name: row.get("dept_name")
+        },
+        serial: row.get::<i64, _>("course_serial"),
+        name: row.get("course_name"),
+        description: row.get("course_description"),
+        book: row.get("course_book"),
+        prompt: row.get("course_prompt"),
+        level: row.get("course_level"),
+        status: row.get("course_status"),
+        priority: row.get("course_priority"),
+        term_id: row.get("course_term_id"),
+        estimated_minutes: None,
+        seconds_spent: 0,
+        weeks: Vec::new()
+    };
+
+    let week_rows = sqlx::query(
+        r#"
+        SELECT id, serial, text, date, is_complete
+        FROM weeks
+        WHERE course_id = ?
+        ORDER BY serial
+        "#
+    )
+    .bind(&course_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut week_map: HashMap<String, Week> = HashMap::new();
+    let mut week_ids = Vec::new();
+
+    for row in week_rows {
+        let id: String = row.get("id");
+        week_ids.push(id.clone());
+
+        week_map.insert(
+            id.clone(),
+            Week {
+                id,
+                serial: row.get::<i64, _>("serial"),
+                text: row.get("text"),
+                date: row.get::<Option<NaiveDate>, _>("date"),
+                is_complete: row.get("is_complete"),
+                estimated_minutes: None,
+                seconds_spent: 0,
+                targets: Vec::new()
+            }
+        );
+    }
+
+    if !week_ids.is_empty() {
+        let placeholders = week_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+
+        let query = format!(
+            r#"
+            SELECT
+              t.id, t.week_id, t.serial, t.text, t.source, t.is_complete,
+              t.estimated_minutes,
+              COALESCE(SUM(s.duration_seconds), 0) AS seconds_spent
+            FROM targets t
+            LEFT JOIN study_sessions s ON s.target_id = t.id
+            WHERE t.week_id IN ({})
+            GROUP BY t.id
+            ORDER BY t.serial
+            "#,
+            placeholders
+        );
+
+        let mut q = sqlx::query(&query);
+        for id in &week_ids {
+            q = q.bind(id);
+        }
+
+        let target_rows = q
+            .fetch_all(pool)
+            .await?;
+
+        for row in target_rows {
+            let week_id: String = row.get("week_id");
+
+            if let Some(week) = week_map.get_mut(&week_id) {
+                let seconds_spent: i64 = row.get("seconds_spent");
+                week.seconds_spent += seconds_spent;
+
+                week.targets.push(Target {
+                    id: row.get("id"),
+                    serial: row.get::<i64, _>("serial"),
+                    text: row.get("text"),
+                    source: row.get("source"),
+                    is_complete: row.get("is_complete"),
+                    estimated_minutes: row.get("estimated_minutes"),
+                    seconds_spent
+                });
+            }
+        }
+    }
+
+    let mut weeks: Vec<Week> = week_map.into_values().collect();
+    weeks.sort_by_key(|w| w.serial);
+
+    for week in &mut weeks {
+        week.estimated_minutes = week
+            .targets
+            .iter()
+            .filter_map(|t| t.estimated_minutes)
+            .reduce(|a, b| a + b);
+    }
+
+    course.estimated_minutes = weeks
+        .iter()
+        .filter_map(|w| w.estimated_minutes)
+        .reduce(|a, b| a + b);
+    course.seconds_spent = weeks.iter().map(|w| w.seconds_spent).sum();
+    course.weeks = weeks;
+
+    Ok(course)
+}
+
+/// Looks a course up by its code, such as `CS-241`. Unlike the other
+/// commands this does not take ids, so a malformed code is reported as such.
+pub async fn get_course_by_code(
+    pool: &SqlitePool,
+    code: String,
+) -> Result<Course, Error> {
+    let code: CourseCode = code.parse()?;
+    let course_id = find_course_id(pool, &code).await?;
+
+    get_course(pool, course_id).await
+}
+
+/// The SQL condition on `c.deleted_at` that picks courses in or out of the
+/// trash.
+fn trash_condition(in_trash: bool) -> &'static str {
+    if in_trash { "c.deleted_at IS NOT NULL" } else { "c.deleted_at IS NULL" }
+}
+
+async fn find_course(
+    pool: &SqlitePool,
+    code: &CourseCode,
+    in_trash: bool,
+) -> Result<Option<String>, Error> {
+    let query = format!(
+        r#"
+        SELECT c.id
+        FROM courses c
+        JOIN departments d ON c.department_id = d.id
+        WHERE d.code = ? AND c.serial = ? AND {}
+        "#,
+        trash_condition(in_trash)
+    );
+
+    Ok(sqlx::query_scalar(&query)
+        .bind(&code.department)
+        .bind(code.serial)
+        .fetch_optional(pool)
+        .await?)
+}
+
+async fn lookup_course(
+    pool: &SqlitePool,
+    reference: &str,
+    in_trash: bool,
+) -> Result<Option<String>, Error> {
+    let query = format!("SELECT c.id FROM courses c WHERE c.id = ? AND {}", trash_condition(in_trash));
+
+    let existing: Option<String> = sqlx::query_scalar(&query)
+        .bind(reference)
+        .fetch_optional(pool)
+        .await?;
+
+    if existing.is_some() {
+        return Ok(existing);
+    }
+
+    match reference.parse::<CourseCode>() {
+        Ok(code) => find_course(pool, &code, in_trash).await,
+        Err(_) => Ok(None)
+    }
+}
+
+/// Finds the course outside the trash with the given code. Serials are
+/// unique within a department even across the trash, so this matches at
+/// most one course.
+pub async fn find_course_id(
+    pool: &SqlitePool,
+    code: &CourseCode,
+) -> Result<String, Error> {
+    find_course(pool, code, false)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("No course {}", code)))
+}
+
+/// Turns what a caller passed as a course into its id. Every course command
+/// accepts either the id itself or a course code such as `CS-241`, so links
+/// and scripts can use the name people know a course by. Courses in the
+/// trash are not found; see `resolve_trashed_course`.
+pub async fn resolve_course(
+    pool: &SqlitePool,
+    reference: &str,
+) -> Result<String, Error> {
+    lookup_course(pool, reference, false)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("No course {}", reference)))
+}
+
+/// Like `resolve_course`, but only finds courses in the trash, for
+/// restoring and purging them.
+pub async fn resolve_trashed_course(
+    pool: &SqlitePool,
+    reference: &str,
+) -> Result<String, Error> {
+    lookup_course(pool, reference, true)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("No course {} in the trash", reference)))
+}
+
+/// The most courses `get_courses` returns in one page.
+const MAX_PAGE_SIZE: i64 = 1000;
+
+impl CourseSort {
+    /// The SQL expression courses are ordered by. Every expression is
+    /// non-null, so it can be compared against a cursor.
+    fn key(&self) -> &'static str {
+        match self {
+            CourseSort::Code => "printf('%s %010d', dept_code, course_serial)",
+            CourseSort::Priority => "course_priority",
+            CourseSort::Name => "lower(course_name)",
+            // Parts per million rather than a REAL, which would not survive
+            // the round trip through the JSON cursor exactly.
+            CourseSort::Progress => "weeks_complete * 1000000 / MAX(weeks_total, 1)",
+            CourseSort::NextDate => "COALESCE(next_date, '9999-12-31')",
+            CourseSort::LastActivity => "COALESCE(last_activity_at, '')"
+        }
+    }
+}
+
+/// Lists the courses outside the trash that match `query`. A tag filter
+/// matches courses carrying one of the tags themselves or on any of their
+/// weeks or targets. With a `limit`, capped at `MAX_PAGE_SIZE`, results
+/// come in pages: pass the returned `next_cursor` back to get the next one.
+/// Ties in the sort order are broken by course id, so pages never overlap.
+pub async fn get_courses(
+    pool: &SqlitePool,
+    query: CourseQuery,
+) -> Result<CoursePage, Error> {
+    let monday = monday_of(Local::now().date_naive());
+
+    let statuses = serde_json::to_string(&query.statuses)?;
+    let departments = serde_json::to_string(&query.departments)?;
+    let tags = serde_json::to_string(&query.tags)?;
+    let terms = serde_json::to_string(&query.terms)?;
+    let text = query.text.filter(|t| !t.trim().is_empty());
+    let (direction, comparison) = if query.descending { ("DESC", "<") } else { ("ASC", ">") };
+
+    let sql = format!(
+        r#"
+        WITH previews AS (
+          SELECT
+            c.id       AS course_id,
+            c.serial   AS course_serial,
+            c.name     AS course_name,
+            c.level    AS course_level,
+            c.status   AS course_status,
+            c.priority AS course_priority,
+            c.term_id  AS course_term_id,
+            d.code     AS dept_code,
+
+            (SELECT COUNT(*) FROM weeks w WHERE w.course_id = c.id) AS weeks_total,
+            (SELECT COUNT(*) FROM weeks w
+             WHERE w.course_id = c.id AND w.is_complete = TRUE) AS weeks_complete,
+
+            (SELECT COUNT(*) FROM targets t JOIN weeks w ON t.week_id = w.id
+             WHERE w.course_id = c.id) AS targets_total,
+            (SELECT COUNT(*) FROM targets t JOIN weeks w ON t.week_id = w.id
+             WHERE w.course_id = c.id AND t.is_complete = TRUE) AS targets_complete,
+
+            (SELECT MIN(w.date) FROM weeks w
+             WHERE w.course_id = c.id AND w.is_complete = FALSE) AS next_date,
+            (SELECT COUNT(*) FROM weeks w
+             WHERE w.course_id = c.id AND w.is_complete = FALSE
+               AND w.date < ? AND c.status = 'active') AS overdue_weeks,
+
+            (SELECT MAX(COALESCE(s.ended_at, s.started_at))
+             FROM study_sessions s
+             JOIN targets t ON s.target_id = t.id
+             JOIN weeks w ON t.week_id = w.id
+             WHERE w.course_id = c.id) AS last_activity_at
+          FROM courses c
+          JOIN departments d ON c.department_id = d.id
+          WHERE c.deleted_at IS NULL
+            AND (json_array_length(?) = 0 OR c.status IN (SELECT value FROM json_each(?)))
+            AND (json_array_length(?) = 0 OR d.code IN (SELECT value FROM json_each(?)))
+            AND (json_array_length(?) = 0 OR EXISTS (
+              SELECT 1
+              FROM taggings g
+              JOIN tags tg ON g.tag_id = tg.id
+              LEFT JOIN weeks gw ON gw.id = g.week_id
+              LEFT JOIN targets gt ON gt.id = g.target_id
+              LEFT JOIN weeks gtw ON gtw.id = gt.week_id
+              WHERE tg.name IN (SELECT value FROM json_each(?))
+                AND c.id IN (g.course_id, gw.course_id, gtw.course_id)
+            ))
+            AND (json_array_length(?) = 0 OR c.term_id IN (SELECT value FROM json_each(?)))
+            AND (? IS NULL OR instr(
+              lower(d.code || ' ' || c.serial || ' ' || c.name || ' ' || COALESCE(c.description, '')),
+              lower(?)
+            ) > 0)
+        ),
+        keyed AS (
+          SELECT *, {key} AS sort_key
+          FROM previews
+          WHERE ? IS NULL OR (overdue_weeks > 0) = ?
+        )
+        SELECT *, json_object('key', sort_key, 'id', course_id) AS cursor
+        FROM keyed
+        WHERE ? IS NULL
+           OR (sort_key, course_id) {comparison} (json_extract(?, '$.key'), json_extract(?, '$.id'))
+        ORDER BY sort_key {direction}, course_id {direction}
+        LIMIT ?
+        "#,
+        key = query.sort.key(),
+    );
+
+    // One extra row tells whether there is another page.
+    let limit = query.limit.map(|l| l.clamp(1, MAX_PAGE_SIZE));
+
+    let mut rows = sqlx::query(&sql)
+        .bind(monday)
+        .bind(&statuses)
+        .bind(&statuses)
+        .bind(&departments)
+        .bind(&departments)
+        .bind(&tags)
+        .bind(&tags)
+        .bind(&terms)
+        .bind(&terms)
+        .bind(&text)
+        .bind(&text)
+        .bind(query.has_overdue)
+        .bind(query.has_overdue)
+        .bind(&query.cursor)
+        .bind(&query.cursor)
+        .bind(&query.cursor)
+        .bind(limit.map_or(-1, |l| l + 1))
+        .fetch_all(pool)
+        .await?;
+
+    let next_cursor = match limit {
+        Some(limit) if rows.len() as i64 > limit => {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| row.get("cursor"))
+        }
+        _ => None,
+    };
+
+    let courses = rows
+        .into_iter()
+        .map(|row| CoursePreview {
+            id: row.get("course_id"),
+            department: row.get("dept_code"),
+            serial: row.get::<i64, _>("course_serial"),
+            name: row.get("course_name"),
+            level: row.get("course_level"),
+            status: row.get("course_status"),
+            priority: row.get("course_priority"),
+            term_id: row.get("course_term_id"),
+            weeks: WeeksPreview {
+                num_complete: row.get::<i64, _>("weeks_complete"),
+                num_total: row.get::<i64, _>("weeks_total"),
+            },
+            targets: TargetsPreview {
+                num_complete: row.get::<i64, _>("targets_complete"),
+                num_total: row.get::<i64, _>("targets_total"),
+            },
+            next_date: row.get("next_date"),
+            num_overdue_weeks: row.get("overdue_weeks"),
+            last_activity_at: row.get("last_activity_at"),
+        })
+        .collect();
+
+    Ok(CoursePage { courses, next_cursor })
+}
+
+pub async fn update_course(
+    pool: &SqlitePool,
+    course_id: String,
+    draft: CourseContentDraft,
+    origin: Option<ContentOrigin>,
+) -> Result<(), Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    validate_content(&draft)?;
+
+    let mut tx = pool.begin().await?;
+
+    let before = snapshot_content(&mut tx, &course_id).await?;
+
+    save_original_version(&mut tx, &course_id).await?;
+
+    write_content(&mut tx, &course_id, &draft, false).await?;
+
+    save_version(&mut tx, &course_id, &draft, origin.unwrap_or(ContentOrigin::Manual)).await?;
+
+    let after = snapshot_content(&mut tx, &course_id).await?;
+
+    record(
+        &mut tx,
+        "Edit course",
+        Operation::Content { course_id: course_id.clone(), content: before },
+        Operation::Content { course_id, content: after },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Changes the status of a course. Activating it schedules its open weeks
+/// from this Monday on, fitting them into `capacity` minutes per week when
+/// a capacity is set. Active courses with a higher priority keep their
+/// share of the capacity; the new weeks fill what is left. A course in a
+/// term is scheduled from the start of the term at the earliest and skips
+/// its breaks; weeks that end up after the term are reported back.
+pub async fn update_course_status(
+    pool: &SqlitePool,
+    course_id: String,
+    status: String,
+    capacity: Option<i64>,
+) -> Result<Option<TermOverflow>, Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+    let term = get_course_term(pool, &course_id).await?;
+    let mut overflow = None;
+
+    let mut tx = pool.begin().await?;
+
+    let before = snapshot_status(&mut tx, &course_id).await?;
+
+    match status.as_str() {
+        "draft" | "inactive" | "complete" => {
+            sqlx::query(
+                r#"
+                UPDATE courses
+                SET status = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&status)
+            .bind(&course_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        "active" => {
+            sqlx::query(
+                r#"
+                UPDATE courses
+                SET status = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&status)
+            .bind(&course_id)
+            .execute(&mut *tx)
+            .await?;
+
+            let weeks = sqlx::query(
+                r#"
+                SELECT w.id, SUM(t.estimated_minutes) AS estimated_minutes
+                FROM weeks w
+                LEFT JOIN targets t ON t.week_id = w.id
+                WHERE w.course_id = ?
+                AND w.is_complete = false
+                GROUP BY w.id
+                ORDER BY w.serial ASC
+                "#,
+            )
+            .bind(&course_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let mut date = monday_of(Local::now().date_naive());
+
+            let mut breaks = HashSet::new();
+            if let Some(term) = &term {
+                date = date.max(monday_of(term.start_date));
+                breaks.extend(term.break_weeks.iter().copied());
+            }
+
+            let estimates: Vec<Option<i64>> = weeks
+                .iter()
+                .map(|row| row.get("estimated_minutes"))
+                .collect();
+            let committed = match capacity.filter(|c| *c > 0) {
+                Some(capacity) => committed_minutes(&mut tx, &course_id, date, capacity).await?,
+                None => HashMap::new(),
+            };
+            let dates = plan_week_dates(date, &estimates, capacity, &committed, &breaks);
+
+            if let Some(term) = &term {
+                let last_monday = monday_of(term.end_date);
+                let late: Vec<NaiveDate> = dates.iter().copied().filter(|d| *d > last_monday).collect();
+
+                if let Some(last_date) = late.last() {
+                    overflow = Some(TermOverflow {
+                        term: term.name.clone(),
+                        end_date: term.end_date,
+                        num_weeks: late.len() as i64,
+                        last_date: *last_date,
+                    });
+                }
+            }
+
+            for (row, week_date) in weeks.iter().zip(dates) {
+                let week_id: String = row.get("id");
+
+                sqlx::query(
+                    r#"
+                    UPDATE weeks
+                    SET date = ?
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(week_date)
+                .bind(week_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        _ => return Err(Error::Validation("invalid status".to_string())),
+    }
+
+    let after = snapshot_status(&mut tx, &course_id).await?;
+
+    record(&mut tx, "Change course status", before, after).await?;
+
+    tx.commit().await?;
+
+    Ok(overflow)
+}
+
+/// Sets how much a course matters relative to the others. Higher priorities
+/// come first in the schedule and claim capacity first when courses are
+/// activated; the default is 0 and negative values are allowed.
+pub async fn set_course_priority(
+    pool: &SqlitePool,
+    course_id: String,
+    priority: i64,
+) -> Result<(), Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let previous: i64 = sqlx::query_scalar("SELECT priority FROM courses WHERE id = ?")
+        .bind(&course_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE courses SET priority = ? WHERE id = ?")
+        .bind(priority)
+        .bind(&course_id)
+        .execute(&mut *tx)
+        .await?;
+
+    record(
+        &mut tx,
+        "Change course priority",
+        Operation::CoursePriority { course_id: course_id.clone(), priority: previous },
+        Operation::CoursePriority { course_id, priority },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Puts a course in a term, or takes it out of its term with `None`. The
+/// course's weeks keep their dates until it is activated again.
+pub async fn set_course_term(
+    pool: &SqlitePool,
+    course_id: String,
+    term_id: Option<String>,
+) -> Result<(), Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    if let Some(term_id) = &term_id {
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM terms WHERE id = ?")
+            .bind(term_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if exists.is_none() {
+            return Err(Error::NotFound(format!("Term not found: {}", term_id)));
+        }
+    }
+
+    let previous: Option<String> = sqlx::query_scalar("SELECT term_id FROM courses WHERE id = ?")
+        .bind(&course_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE courses SET term_id = ? WHERE id = ?")
+        .bind(&term_id)
+        .bind(&course_id)
+        .execute(&mut *tx)
+        .await?;
+
+    record(
+        &mut tx,
+        "Change course term",
+        Operation::CourseTerm { course_id: course_id.clone(), term_id: previous },
+        Operation::CourseTerm { course_id, term_id },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// The active course to focus on: the one with the highest priority, and
+/// among equals the one whose next open week comes soonest.
+pub async fn get_focus_course(
+    pool: &SqlitePool,
+) -> Result<Option<CourseHeader>, Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT
+          c.id     AS course_id,
+          c.serial AS course_serial,
+          c.name   AS course_name,
+          c.status AS course_status,
+          d.code   AS dept_code
+        FROM courses c
+        JOIN departments d ON c.department_id = d.id
+        WHERE c.status = 'active' AND c.deleted_at IS NULL
+        ORDER BY
+          c.priority DESC,
+          (SELECT COALESCE(MIN(w.date), '9999-12-31') FROM weeks w
+           WHERE w.course_id = c.id AND w.is_complete = FALSE),
+          d.code,
+          c.serial
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| CourseHeader {
+        id: row.get("course_id"),
+        department: row.get("dept_code"),
+        serial: row.get("course_serial"),
+        name: row.get("course_name"),
+        status: row.get("course_status"),
+    }))
+}
+
+/// Moves a course to the trash. Its weeks and targets stay in place and come
+/// back with it on restore; `purge_trash` removes them for good.
+pub async fn delete_course(
+    pool: &SqlitePool,
+    course_id: String,
+) -> Result<(), Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let deleted_at = Utc::now();
+
+    let result = sqlx::query(
+        r#"
+        UPDATE courses
+        SET deleted_at = ?
+        WHERE id = ? AND deleted_at IS NULL
+        "#
+    )
+    .bind(deleted_at)
+    .bind(&course_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() > 0 {
+        record(
+            &mut tx,
+            "Delete course",
+            Operation::Deleted {
+                course_ids: vec![course_id.clone()],
+                deleted_at: None,
+            },
+            Operation::Deleted {
+                course_ids: vec![course_id],
+                deleted_at: Some(deleted_at),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Moves a course to the department `department_code`, numbered `serial` or,
+/// without one, a generated serial. A course that stays in its department
+/// keeps its serial unless a new one is given.
+pub async fn move_course(
+    pool: &SqlitePool,
+    course_id: String,
+    department_code: String,
+    serial: Option<i64>,
+) -> Result<i64, Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let department_id: String = sqlx::query_scalar(
+        "SELECT id FROM departments WHERE code = ? AND deleted_at IS NULL"
+    )
+    .bind(&department_code)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Unknown department code: {}", department_code)))?;
+
+    let row = sqlx::query("SELECT department_id, serial, level FROM courses WHERE id = ?")
+        .bind(&course_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Unknown course: {}", course_id)))?;
+
+    let previous = Operation::CourseNumber {
+        course_id: course_id.clone(),
+        department_id: row.get("department_id"),
+        serial: row.get("serial"),
+    };
+    let stays = row.get::<String, _>("department_id") == department_id;
+
+    let serial = match serial {
+        Some(serial) => {
+            ensure_serial_free(&mut tx, &department_id, serial, Some(&course_id)).await?;
+            serial
+        }
+        None if stays => row.get("serial"),
+        None => generate_course_serial(&mut tx, &department_id, row.get("level")).await?,
+    };
+
+    sqlx::query(
+        "UPDATE courses SET department_id = ?, serial = ? WHERE id = ?"
+    )
+    .bind(&department_id)
+    .bind(serial)
+    .bind(&course_id)
+    .execute(&mut *tx)
+    .await?;
+
+    record(
+        &mut tx,
+        "Move course",
+        previous,
+        Operation::CourseNumber { course_id, department_id, serial },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(serial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory;
+    use crate::journal::undo;
+    use crate::sessions::log_session;
+    use crate::types::{TargetDraft, WeekDraft};
+
+    #[tokio::test]
+    async fn editing_a_course_keeps_study_sessions() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            INSERT INTO weeks (id, course_id, serial, text) VALUES ('sorting', 'algo', 1, 'Sorting'), ('graphs', 'algo', 2, 'Graphs');
+            INSERT INTO targets (id, week_id, serial, text, source) VALUES
+              ('read', 'sorting', 1, 'Read', 'Book'),
+              ('exercises', 'sorting', 2, 'Exercises', 'Book'),
+              ('bfs', 'graphs', 1, 'BFS', 'Book');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        log_session(&pool, "read".to_string(), Utc::now(), 30).await.unwrap();
+
+        let target = |serial: i64, text: &str| TargetDraft {
+            serial,
+            text: text.to_string(),
+            source: "Book".to_string(),
+            estimated_minutes: None
+        };
+        let draft = CourseContentDraft {
+            name: "Algorithms".to_string(),
+            description: None,
+            book: None,
+            prompt: None,
+            weeks: vec![WeekDraft {
+                serial: 1,
+                text: "Sorting and searching".to_string(),
+                targets: vec![target(1, "Read chapter 2"), target(2, "Exercises")]
+            }]
+        };
+        update_course(&pool, "algo".to_string(), draft, None).await.unwrap();
+
+        let course = get_course(&pool, "algo".to_string()).await.unwrap();
+        assert_eq!(course.weeks.len(), 1);
+        assert_eq!(course.weeks[0].targets[0].id, "read");
+        assert_eq!(course.weeks[0].targets[0].seconds_spent, 30 * 60);
+        assert_eq!(course.seconds_spent, 30 * 60);
+    }
+
+    #[test]
+    fn course_codes_parse() {
+        let code: CourseCode = "CS-241".parse().unwrap();
+        assert_eq!((code.department.as_str(), code.serial), ("CS", 241));
+
+        let code: CourseCode = " MATH 101 ".parse().unwrap();
+        assert_eq!((code.department.as_str(), code.serial), ("MATH", 101));
+
+        assert_eq!(code.to_string(), "MATH-101");
+
+        for input in ["", "241", "CS", "CS-", "CS-24x"] {
+            assert!(matches!(input.parse::<CourseCode>(), Err(Error::Validation(_))), "{:?}", input);
+        }
+    }
+
+    fn monday(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, day).unwrap()
+    }
+
+    #[test]
+    fn weeks_without_a_capacity_get_a_week_each() {
+        let breaks = HashSet::from([monday(12)]);
+        let dates = plan_week_dates(monday(5), &[None, Some(600), None], None, &HashMap::new(), &breaks);
+
+        assert_eq!(dates, vec![monday(5), monday(19), monday(26)]);
+    }
+
+    #[test]
+    fn weeks_are_packed_into_the_capacity() {
+        let estimates = [Some(100), Some(100), Some(200), None];
+        let dates = plan_week_dates(monday(5), &estimates, Some(300), &HashMap::new(), &HashSet::new());
+
+        assert_eq!(dates, vec![monday(5), monday(5), monday(12), monday(19)]);
+    }
+
+    #[test]
+    fn committed_minutes_and_breaks_are_planned_around() {
+        let committed = HashMap::from([(monday(5), 250)]);
+        let breaks = HashSet::from([monday(12)]);
+        let dates = plan_week_dates(monday(5), &[Some(50), Some(100)], Some(300), &committed, &breaks);
+
+        assert_eq!(dates, vec![monday(5), monday(19)]);
+    }
+
+    #[tokio::test]
+    async fn courses_are_paged_with_a_cursor() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES
+              ('c', 'cs', 101, 'Programming'),
+              ('b', 'cs', 102, 'Discrete Mathematics'),
+              ('a', 'cs', 103, 'Data Structures');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut serials = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let query = CourseQuery { limit: Some(2), cursor, ..CourseQuery::default() };
+            let page = get_courses(&pool, query).await.unwrap();
+
+            serials.extend(page.courses.iter().map(|c| c.serial));
+            assert!(page.courses.len() <= 2 && serials.len() <= 3);
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(serials, vec![101, 102, 103]);
+    }
+
+    #[tokio::test]
+    async fn progress_pages_neither_repeat_nor_skip_ties() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES
+              ('a', 'cs', 101, 'Programming'),
+              ('b', 'cs', 102, 'Discrete Mathematics'),
+              ('c', 'cs', 103, 'Data Structures'),
+              ('d', 'cs', 104, 'Algorithms');
+            INSERT INTO weeks (id, course_id, serial, text, is_complete) VALUES
+              ('a1', 'a', 1, 'One', TRUE), ('a2', 'a', 2, 'Two', FALSE), ('a3', 'a', 3, 'Three', FALSE),
+              ('b1', 'b', 1, 'One', TRUE), ('b2', 'b', 2, 'Two', FALSE), ('b3', 'b', 3, 'Three', FALSE),
+              ('c1', 'c', 1, 'One', TRUE), ('c2', 'c', 2, 'Two', TRUE), ('c3', 'c', 3, 'Three', FALSE);
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for (descending, expected) in [(false, ["d", "a", "b", "c"]), (true, ["c", "b", "a", "d"])] {
+            let mut ids = Vec::new();
+            let mut cursor = None;
+
+            loop {
+                let query = CourseQuery {
+                    sort: CourseSort::Progress,
+                    descending,
+                    limit: Some(1),
+                    cursor,
+                    ..CourseQuery::default()
+                };
+                let page = get_courses(&pool, query).await.unwrap();
+                ids.extend(page.courses.into_iter().map(|c| c.id));
+                assert!(ids.len() <= expected.len(), "pages repeat: {:?}", ids);
+
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+
+            assert_eq!(ids, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn huge_page_sizes_are_capped() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let query = CourseQuery { limit: Some(i64::MAX), ..CourseQuery::default() };
+        let page = get_courses(&pool, query).await.unwrap();
+
+        assert_eq!(page.courses.len(), 1);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn pinned_serials_must_be_in_the_bands() {
+        let pool = memory().await.unwrap();
+        for serial in [0, -5, 99, 600] {
+            let draft = CourseDraft {
+                department: "CS".to_string(),
+                name: "Algorithms".to_string(),
+                description: None,
+                book: None,
+                prompt: None,
+                level: None,
+                serial: Some(serial)
+            };
+
+            let created = create_courses(&pool, vec![draft], vec![DepartmentDraft {
+                code: "CS".to_string(),
+                name: "Computer Science".to_string()
+            }])
+            .await;
+            assert!(matches!(created, Err(Error::Validation(_))), "{}", serial);
+        }
+    }
+
+    #[tokio::test]
+    async fn moves_keep_their_serial_and_can_be_undone() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science'), ('math', 'MATH', 'Mathematics');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let serial = move_course(&pool, "algo".to_string(), "CS".to_string(), None).await.unwrap();
+        assert_eq!(serial, 241);
+
+        move_course(&pool, "algo".to_string(), "MATH".to_string(), Some(310)).await.unwrap();
+        undo(&pool).await.unwrap();
+
+        let course = get_course(&pool, "algo".to_string()).await.unwrap();
+        assert_eq!((course.department.code.as_str(), course.serial), ("CS", 241));
+    }
+}
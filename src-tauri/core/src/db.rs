@@ -0,0 +1,160 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::Error;
+
+/// Quotes `value` as an SQL string literal, for statements like `PRAGMA key`
+/// and `ATTACH ... KEY` that do not accept bound parameters everywhere.
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Whether the file at `path` is encrypted. Plain SQLite files start with a
+/// fixed header, while SQLCipher files are indistinguishable from noise.
+/// Missing and empty files count as plain.
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+
+    match File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header != b"SQLite format 3\0",
+        Err(_) => false
+    }
+}
+
+/// Deletes the WAL and shared-memory files that belong to the database at
+/// `path`, after it has been closed and its file replaced.
+pub fn remove_sidecars(path: &Path) {
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let _ = std::fs::remove_file(sidecar);
+    }
+}
+
+/// Turns the error from opening an encrypted database with the wrong
+/// passphrase into something readable.
+pub fn unlock_error(e: sqlx::Error) -> Error {
+    if e.to_string().contains("file is not a database") {
+        Error::Validation("Incorrect passphrase".to_string())
+    } else {
+        e.into()
+    }
+}
+
+/// Brings the schema of the database behind `pool` up to date.
+pub async fn migrate(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::migrate!("./migrations").run(pool).await?;
+
+    Ok(())
+}
+
+/// Opens the database at `path`, unlocking it with `passphrase` when given,
+/// and brings its schema up to date.
+pub async fn connect(path: &Path, passphrase: Option<&str>) -> Result<SqlitePool, sqlx::Error> {
+    let mut options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+
+    if let Some(passphrase) = passphrase {
+        options = options.pragma("key", quote(passphrase));
+    }
+
+    let pool = SqlitePool::connect_with(options).await?;
+    migrate(&pool).await?;
+
+    Ok(pool)
+}
+
+/// Opens a fresh in-memory database with the full schema. It lives on a
+/// single connection, since every connection to `:memory:` gets its own
+/// empty database.
+pub async fn memory() -> Result<SqlitePool, sqlx::Error> {
+    let pool = empty_memory().await?;
+
+    migrate(&pool).await?;
+
+    Ok(pool)
+}
+
+async fn empty_memory() -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .in_memory(true)
+        .foreign_keys(true);
+
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await
+}
+
+/// Turns free-form user input into an FTS5 MATCH expression, quoting every
+/// term so punctuation like `C++` or stray quotes cannot break the query.
+/// Each term is treated as a prefix, so "eigen" also finds "eigenvalues".
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the migrations from `from` up to but not including `until`.
+    async fn apply(pool: &SqlitePool, from: i64, until: i64) {
+        for migration in sqlx::migrate!("./migrations").iter() {
+            if (from..until).contains(&migration.version) {
+                sqlx::raw_sql(&migration.sql).execute(pool).await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn migration_008_renumbers_only_duplicate_serials() {
+        let pool = empty_memory().await.unwrap();
+        apply(&pool, 1, 8).await;
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science'), ('math', 'MATH', 'Mathematics');
+            INSERT INTO courses (id, department_id, serial, name) VALUES
+              ('a', 'cs', 101, 'A'), ('b', 'cs', 101, 'B'), ('c', 'cs', 101, 'C'),
+              ('d', 'cs', 102, 'D'), ('e', 'cs', 102, 'E'), ('f', 'math', 101, 'F');
+            INSERT INTO weeks (id, course_id, serial, text) VALUES
+              ('w1', 'a', 1, 'One'), ('w2', 'a', 1, 'Two'), ('w3', 'a', 2, 'Three'),
+              ('w4', 'a', 4, 'Four'), ('w5', 'd', 1, 'Only');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        apply(&pool, 8, 9).await;
+
+        let courses: Vec<(String, i64)> = sqlx::query_as("SELECT id, serial FROM courses ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let expected = [("a", 101), ("b", 103), ("c", 104), ("d", 102), ("e", 105), ("f", 101)];
+        assert_eq!(courses, expected.map(|(id, serial)| (id.to_string(), serial)));
+
+        let weeks: Vec<(String, i64)> = sqlx::query_as("SELECT id, serial FROM weeks ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let expected = [("w1", 1), ("w2", 5), ("w3", 2), ("w4", 4), ("w5", 1)];
+        assert_eq!(weeks, expected.map(|(id, serial)| (id.to_string(), serial)));
+    }
+}
@@ -0,0 +1,483 @@
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::courses::generate_course_serial;
+use crate::error::Error;
+use crate::journal::{apply, forget_departments, record, Operation};
+use crate::types::{CourseLevel, Department, DepartmentDeletePolicy, DepartmentDraft};
+
+type Tx<'a> = sqlx::Transaction<'a, sqlx::Sqlite>;
+
+async fn ensure_code_free(
+    tx: &mut Tx<'_>,
+    code: &str,
+    except_id: Option<&str>,
+) -> Result<(), Error> {
+    let existing: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM departments WHERE code = ? AND deleted_at IS NULL"
+    )
+    .bind(code)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match existing {
+        Some(id) if Some(id.as_str()) != except_id => {
+            Err(Error::Conflict(format!("Department code already in use: {}", code)))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_departments(
+    pool: &SqlitePool,
+) -> Result<Vec<Department>, Error> {
+    let rows = sqlx::query("SELECT id, code, name FROM departments WHERE deleted_at IS NULL ORDER BY code")
+        .fetch_all(pool)
+        .await?;
+
+    let departments = rows
+        .into_iter()
+        .map(|row| Department {
+            id: row.get::<String, _>("id"),
+            code: row.get::<String, _>("code"),
+            name: row.get::<String, _>("name")
+        })
+        .collect();
+
+    Ok(departments)
+}
+
+pub async fn create_department(
+    pool: &SqlitePool,
+    department: DepartmentDraft,
+) -> Result<Department, Error> {
+    let mut tx = pool.begin().await?;
+
+    ensure_code_free(&mut tx, &department.code, None).await?;
+
+    let id = Uuid::new_v4().to_string();
+
+    // A department still waiting for its trashed courses to be purged is
+    // brought back rather than colliding with the new one.
+    let id: String = sqlx::query_scalar(
+        "INSERT INTO departments (id, code, name)
+         VALUES (?, ?, ?)
+         ON CONFLICT (code) DO UPDATE SET name = excluded.name, deleted_at = NULL
+         RETURNING id"
+    )
+    .bind(&id)
+    .bind(&department.code)
+    .bind(&department.name)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Department {
+        id,
+        code: department.code,
+        name: department.name
+    })
+}
+
+pub async fn rename_department(
+    pool: &SqlitePool,
+    department_id: String,
+    department: DepartmentDraft,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    ensure_code_free(&mut tx, &department.code, Some(&department_id)).await?;
+
+    let result = sqlx::query(
+        "UPDATE departments SET code = ?, name = ? WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&department.code)
+    .bind(&department.name)
+    .bind(&department_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Unknown department: {}", department_id)));
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Deletes a department. With `Cascade`, its courses are moved to the trash
+/// and the department is kept out of sight until the last of them is
+/// purged. Restoring one of them brings the department back. Both can be
+/// undone together.
+pub async fn delete_department(
+    pool: &SqlitePool,
+    department_id: String,
+    policy: DepartmentDeletePolicy,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    let exists: Option<i64> = sqlx::query_scalar(
+        "SELECT 1 FROM departments WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&department_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if exists.is_none() {
+        return Err(Error::NotFound(format!("Unknown department: {}", department_id)));
+    }
+
+    let num_courses: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM courses WHERE department_id = ?"
+    )
+    .bind(&department_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    match policy {
+        DepartmentDeletePolicy::Restrict if num_courses > 0 => {
+            return Err(Error::Conflict(format!("Department still has {} courses", num_courses)));
+        }
+        DepartmentDeletePolicy::Cascade if num_courses > 0 => {
+            let deleted_at = Utc::now();
+
+            let course_ids: Vec<String> = sqlx::query_scalar(
+                "SELECT id FROM courses WHERE department_id = ? AND deleted_at IS NULL"
+            )
+            .bind(&department_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let undo = Operation::DepartmentDeleted {
+                department_id: department_id.clone(),
+                course_ids: course_ids.clone(),
+                deleted_at: None,
+            };
+            let redo = Operation::DepartmentDeleted {
+                department_id,
+                course_ids,
+                deleted_at: Some(deleted_at),
+            };
+
+            apply(&mut tx, &redo).await?;
+            record(&mut tx, "Delete department", undo, redo).await?;
+        }
+        DepartmentDeletePolicy::Restrict | DepartmentDeletePolicy::Cascade => {
+            forget_departments(&mut tx, std::slice::from_ref(&department_id)).await?;
+
+            sqlx::query("DELETE FROM departments WHERE id = ?")
+                .bind(&department_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Brings back the departments of `course_ids` that were waiting for their
+/// courses to be purged, for when those courses leave the trash.
+pub(crate) async fn revive_departments(
+    tx: &mut Tx<'_>,
+    course_ids: &[String],
+) -> Result<(), Error> {
+    sqlx::query(
+        "UPDATE departments SET deleted_at = NULL
+         WHERE deleted_at IS NOT NULL
+           AND id IN (
+             SELECT department_id FROM courses
+             WHERE id IN (SELECT value FROM json_each(?))
+           )"
+    )
+    .bind(serde_json::to_string(course_ids)?)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves every course from `source_id` into `target_id` and removes the
+/// source department. Courses whose serial is already taken in the target
+/// department get a freshly generated one.
+pub async fn merge_departments(
+    pool: &SqlitePool,
+    source_id: String,
+    target_id: String,
+) -> Result<(), Error> {
+    if source_id == target_id {
+        return Err(Error::Validation("Cannot merge a department into itself".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let target_exists: Option<i64> = sqlx::query_scalar(
+        "SELECT 1 FROM departments WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&target_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if target_exists.is_none() {
+        return Err(Error::NotFound(format!("Unknown department: {}", target_id)));
+    }
+
+    let courses = sqlx::query(
+        "SELECT id, serial, level FROM courses WHERE department_id = ? ORDER BY serial"
+    )
+    .bind(&source_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for row in courses {
+        let course_id: String = row.get("id");
+        let serial: i64 = row.get("serial");
+        let level: Option<CourseLevel> = row.get("level");
+
+        let clash: Option<i64> = sqlx::query_scalar(
+            "SELECT 1 FROM courses WHERE department_id = ? AND serial = ?"
+        )
+        .bind(&target_id)
+        .bind(serial)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let serial = match clash {
+            Some(_) => generate_course_serial(&mut tx, &target_id, level).await?,
+            None => serial,
+        };
+
+        sqlx::query(
+            "UPDATE courses SET department_id = ?, serial = ? WHERE id = ?"
+        )
+        .bind(&target_id)
+        .bind(serial)
+        .bind(&course_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    forget_departments(&mut tx, std::slice::from_ref(&source_id)).await?;
+
+    let result = sqlx::query("DELETE FROM departments WHERE id = ? AND deleted_at IS NULL")
+        .bind(&source_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Unknown department: {}", source_id)));
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Renumbers the courses of a department so each level band is numbered
+/// consecutively from x01, keeping the existing relative order. Courses are
+/// grouped by their level, or by the band their serial is in when they have
+/// none, so a course pinned outside its level's band moves into it. Courses
+/// numbered past the bands, which only happens once a band is full, keep
+/// their number.
+pub async fn renumber_department(
+    pool: &SqlitePool,
+    department_id: String,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    let exists: Option<i64> = sqlx::query_scalar(
+        "SELECT 1 FROM departments WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&department_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if exists.is_none() {
+        return Err(Error::NotFound(format!("Unknown department: {}", department_id)));
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, serial, level FROM courses
+         WHERE department_id = ? AND serial BETWEEN 100 AND 599
+         ORDER BY serial"
+    )
+    .bind(&department_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut bands: Vec<(CourseLevel, Vec<String>)> = Vec::new();
+
+    for row in rows {
+        let serial: i64 = row.get("serial");
+        let level = row
+            .get::<Option<CourseLevel>, _>("level")
+            .or_else(|| CourseLevel::from_serial(serial));
+
+        let Some(level) = level else {
+            continue;
+        };
+
+        match bands.iter_mut().find(|(l, _)| *l == level) {
+            Some((_, ids)) => ids.push(row.get("id")),
+            None => bands.push((level, vec![row.get("id")])),
+        }
+    }
+
+    // Park the affected courses on negative serials first so the new
+    // numbers never collide with ones that have not been moved yet.
+    sqlx::query(
+        "UPDATE courses SET serial = -serial
+         WHERE department_id = ? AND serial BETWEEN 100 AND 599"
+    )
+    .bind(&department_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for (level, ids) in &bands {
+        let (lower, upper) = level.band();
+
+        if ids.len() as i64 > upper - lower {
+            return Err(Error::Conflict(format!("Too many {:?} courses to fit their band", level)));
+        }
+
+        for (i, id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE courses SET serial = ? WHERE id = ?")
+                .bind(lower + 1 + i as i64)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory;
+    use crate::journal::{redo, undo};
+    use crate::trash::{list_trash, purge_trash, restore_course};
+
+    fn draft(code: &str) -> DepartmentDraft {
+        DepartmentDraft { code: code.to_string(), name: code.to_string() }
+    }
+
+    async fn department_ids(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar("SELECT id FROM departments ORDER BY code")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn unknown_departments_are_not_found() {
+        let pool = memory().await.unwrap();
+        let target = create_department(&pool, draft("MA")).await.unwrap();
+
+        let renamed = rename_department(&pool, "missing".to_string(), draft("PH")).await;
+        assert!(matches!(renamed, Err(Error::NotFound(_))));
+
+        let merged = merge_departments(&pool, "missing".to_string(), target.id).await;
+        assert!(matches!(merged, Err(Error::NotFound(_))));
+
+        let deleted = delete_department(&pool, "missing".to_string(), DepartmentDeletePolicy::Cascade).await;
+        assert!(matches!(deleted, Err(Error::NotFound(_))));
+
+        let renumbered = renumber_department(&pool, "missing".to_string()).await;
+        assert!(matches!(renumbered, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn renumbering_fills_bands_and_keeps_larger_serials() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name, level) VALUES
+              ('unlevelled', 'cs', 150, 'Programming', NULL),
+              ('intro', 'cs', 120, 'Computing', 'intro'),
+              ('misplaced', 'cs', 250, 'Logic', 'intro'),
+              ('overflow', 'cs', 1234, 'Seminar', 'intro'),
+              ('advanced', 'cs', 480, 'Compilers', NULL);
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        renumber_department(&pool, "cs".to_string()).await.unwrap();
+
+        let serials: Vec<(String, i64)> = sqlx::query_as("SELECT id, serial FROM courses ORDER BY serial")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let expected = [("intro", 101), ("unlevelled", 102), ("misplaced", 103), ("advanced", 301), ("overflow", 1234)];
+        assert_eq!(serials, expected.map(|(id, serial)| (id.to_string(), serial)));
+    }
+
+    #[tokio::test]
+    async fn cascade_moves_courses_to_the_trash() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES
+              ('algo', 'cs', 241, 'Algorithms'),
+              ('os', 'cs', 342, 'Operating Systems');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        delete_department(&pool, "cs".to_string(), DepartmentDeletePolicy::Cascade).await.unwrap();
+
+        assert!(get_departments(&pool).await.unwrap().is_empty());
+        assert_eq!(list_trash(&pool).await.unwrap().len(), 2);
+
+        undo(&pool).await.unwrap();
+        assert_eq!(get_departments(&pool).await.unwrap().len(), 1);
+        assert!(list_trash(&pool).await.unwrap().is_empty());
+
+        redo(&pool).await.unwrap();
+        restore_course(&pool, "os".to_string()).await.unwrap();
+        assert_eq!(get_departments(&pool).await.unwrap().len(), 1);
+
+        delete_department(&pool, "cs".to_string(), DepartmentDeletePolicy::Cascade).await.unwrap();
+        purge_trash(&pool, Some("algo".to_string())).await.unwrap();
+        assert_eq!(department_ids(&pool).await, vec!["cs"]);
+
+        purge_trash(&pool, None).await.unwrap();
+        assert!(department_ids(&pool).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn departments_waiting_for_a_purge_can_be_recreated() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        delete_department(&pool, "cs".to_string(), DepartmentDeletePolicy::Cascade).await.unwrap();
+
+        let department = create_department(&pool, draft("CS")).await.unwrap();
+        assert_eq!(department.id, "cs");
+        assert_eq!(get_departments(&pool).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,113 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// The error returned by every command. It reaches the frontend as
+/// `{ code, message }` so callers can branch on `code` and show `message`.
+#[derive(Debug)]
+pub enum Error {
+    /// The requested record does not exist.
+    NotFound(String),
+    /// The input was rejected before anything was changed.
+    Validation(String),
+    /// The change clashes with existing data, such as a taken serial.
+    Conflict(String),
+    Database(sqlx::Error),
+    /// A language model request failed or returned something unusable. The
+    /// requests are made by the frontend, which reports them with this code.
+    Llm(String),
+    Io(std::io::Error),
+}
+
+impl Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::NotFound(_) => "notFound",
+            Error::Validation(_) => "validation",
+            Error::Conflict(_) => "conflict",
+            Error::Database(_) => "database",
+            Error::Llm(_) => "llm",
+            Error::Io(_) => "io",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound(message)
+            | Error::Validation(message)
+            | Error::Conflict(message)
+            | Error::Llm(message) => f.write_str(message),
+            Error::Database(e) => e.fmt(f),
+            Error::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("Error", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => Error::NotFound("Record not found".to_string()),
+            sqlx::Error::Database(db)
+                if db.is_unique_violation() || db.is_foreign_key_violation() =>
+            {
+                Error::Conflict(db.message().to_string())
+            }
+            sqlx::Error::Database(db) if db.is_check_violation() => {
+                Error::Validation(db.message().to_string())
+            }
+            _ => Error::Database(e),
+        }
+    }
+}
+
+impl From<sqlx::migrate::MigrateError> for Error {
+    fn from(e: sqlx::migrate::MigrateError) -> Self {
+        Error::Database(e.into())
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// Only a failure to read or write is an I/O error. JSON that does not parse
+/// or does not have the expected shape, such as a malformed archive or
+/// cursor, is bad input.
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            Error::Io(e.into())
+        } else {
+            Error::Validation(e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_json_is_a_validation_error() {
+        let syntax = serde_json::from_str::<i64>("{").unwrap_err();
+        let data = serde_json::from_str::<i64>("\"text\"").unwrap_err();
+
+        assert_eq!(Error::from(syntax).code(), "validation");
+        assert_eq!(Error::from(data).code(), "validation");
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::courses::{generate_course_serial, get_course, validate_content, write_content};
+use crate::error::Error;
+use crate::journal::{record, Operation};
+use crate::types::{ArchivedCourse, ContentOrigin, CourseArchive, CourseContentDraft, CourseHeader, CourseStatus, DepartmentDraft, TargetDraft, WeekDraft};
+use crate::versions::save_version;
+
+/// Bumped whenever the archive format changes in a way older readers cannot
+/// handle.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Writes the given courses, or every course outside the trash, to an
+/// archive that `import_courses` can read back into any library.
+pub async fn export_courses(
+    pool: &SqlitePool,
+    course_ids: Option<Vec<String>>,
+) -> Result<CourseArchive, Error> {
+    let course_ids = match course_ids {
+        Some(course_ids) => course_ids,
+        None => sqlx::query_scalar(
+            r#"
+            SELECT c.id
+            FROM courses c
+            JOIN departments d ON c.department_id = d.id
+            WHERE c.deleted_at IS NULL
+            ORDER BY d.code, c.serial
+            "#
+        )
+        .fetch_all(pool)
+        .await?,
+    };
+
+    let mut departments: Vec<DepartmentDraft> = Vec::new();
+    let mut courses = Vec::new();
+
+    for course_id in course_ids {
+        let course = get_course(pool, course_id).await?;
+
+        if !departments.iter().any(|d| d.code == course.department.code) {
+            departments.push(DepartmentDraft {
+                code: course.department.code.clone(),
+                name: course.department.name.clone(),
+            });
+        }
+
+        let weeks = course
+            .weeks
+            .into_iter()
+            .map(|week| WeekDraft {
+                serial: week.serial,
+                text: week.text,
+                targets: week
+                    .targets
+                    .into_iter()
+                    .map(|target| TargetDraft {
+                        serial: target.serial,
+                        text: target.text,
+                        source: target.source,
+                        estimated_minutes: target.estimated_minutes,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        courses.push(ArchivedCourse {
+            department: course.department.code,
+            serial: course.serial,
+            level: course.level,
+            content: CourseContentDraft {
+                name: course.name,
+                description: course.description,
+                book: course.book,
+                prompt: course.prompt,
+                weeks,
+            },
+        });
+    }
+
+    Ok(CourseArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        departments,
+        courses,
+    })
+}
+
+/// Adds the courses in `archive` to the library as drafts. Departments are
+/// created when their code is new and left alone otherwise. A course keeps
+/// its serial unless it is taken, in which case it gets a fresh one in its
+/// level's band.
+pub async fn import_courses(
+    pool: &SqlitePool,
+    archive: CourseArchive,
+) -> Result<Vec<CourseHeader>, Error> {
+    if archive.version > ARCHIVE_VERSION {
+        return Err(Error::Validation(format!(
+            "Unsupported archive version: {}",
+            archive.version
+        )));
+    }
+
+    for course in &archive.courses {
+        validate_content(&course.content)?;
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for dept in &archive.departments {
+        sqlx::query(
+            "INSERT INTO departments (id, code, name)
+             VALUES (?, ?, ?)
+             ON CONFLICT (code) DO UPDATE SET deleted_at = NULL"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&dept.code)
+        .bind(&dept.name)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let dept_map: HashMap<String, String> = sqlx::query("SELECT id, code FROM departments")
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|row| (row.get("code"), row.get("id")))
+        .collect();
+
+    let mut imported = Vec::new();
+
+    for course in archive.courses {
+        let department_id = dept_map
+            .get(&course.department)
+            .ok_or_else(|| Error::NotFound(format!("Unknown department code: {}", course.department)))?;
+
+        let taken: Option<i64> = sqlx::query_scalar(
+            "SELECT 1 FROM courses WHERE department_id = ? AND serial = ?"
+        )
+        .bind(department_id)
+        .bind(course.serial)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // Serials below 1 are never handed out, so one in an archive is
+        // replaced like a taken one.
+        let serial = match taken {
+            None if course.serial > 0 => course.serial,
+            _ => generate_course_serial(&mut tx, department_id, course.level).await?,
+        };
+
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            "INSERT INTO courses
+            (id, department_id, serial, name, prompt, level)
+            VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&id)
+        .bind(department_id)
+        .bind(serial)
+        .bind(&course.content.name)
+        .bind(&course.content.prompt)
+        .bind(course.level)
+        .execute(&mut *tx)
+        .await?;
+
+        write_content(&mut tx, &id, &course.content, false).await?;
+        save_version(&mut tx, &id, &course.content, ContentOrigin::Import).await?;
+
+        imported.push(CourseHeader {
+            id,
+            department: course.department,
+            serial,
+            name: course.content.name,
+            status: CourseStatus::Draft,
+        });
+    }
+
+    let course_ids: Vec<String> = imported.iter().map(|c| c.id.clone()).collect();
+
+    record(
+        &mut tx,
+        "Import courses",
+        Operation::Imported {
+            course_ids: course_ids.clone(),
+            imported: false,
+        },
+        Operation::Imported {
+            course_ids,
+            imported: true,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::courses::resolve_course;
+    use crate::db::memory;
+    use crate::journal::{redo, undo};
+
+    #[tokio::test]
+    async fn undoing_an_import_moves_its_courses_to_the_trash() {
+        let pool = memory().await.unwrap();
+
+        let archive = CourseArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            departments: vec![DepartmentDraft {
+                code: "CS".to_string(),
+                name: "Computer Science".to_string()
+            }],
+            courses: vec![ArchivedCourse {
+                department: "CS".to_string(),
+                serial: 241,
+                level: None,
+                content: CourseContentDraft {
+                    name: "Algorithms".to_string(),
+                    description: None,
+                    book: None,
+                    prompt: None,
+                    weeks: Vec::new()
+                }
+            }]
+        };
+
+        let imported = import_courses(&pool, archive).await.unwrap();
+
+        undo(&pool).await.unwrap();
+        let trashed: bool = sqlx::query_scalar("SELECT deleted_at IS NOT NULL FROM courses WHERE id = ?")
+            .bind(&imported[0].id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(trashed);
+
+        redo(&pool).await.unwrap();
+        assert_eq!(resolve_course(&pool, "CS-241").await.unwrap(), imported[0].id);
+    }
+}
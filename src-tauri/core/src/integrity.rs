@@ -0,0 +1,366 @@
+use chrono::NaiveDate;
+use sqlx::{Row, SqlitePool};
+
+use crate::courses::generate_course_serial;
+use crate::error::Error;
+use crate::schedule::monday_of;
+use crate::types::{CourseLevel, IntegrityIssue, IntegrityIssueKind};
+
+type Tx<'a> = sqlx::Transaction<'a, sqlx::Sqlite>;
+
+/// Rows whose parent no longer exists, as (table, key, description, query).
+/// The query selects the key of each dangling row; tables without an id are
+/// keyed by rowid.
+const DANGLING_CHECKS: &[(&str, &str, &str, &str)] = &[
+    (
+        "courses",
+        "id",
+        "courses without a department",
+        "SELECT id FROM courses WHERE department_id NOT IN (SELECT id FROM departments)",
+    ),
+    (
+        "weeks",
+        "id",
+        "weeks without a course",
+        "SELECT id FROM weeks WHERE course_id NOT IN (SELECT id FROM courses)",
+    ),
+    (
+        "targets",
+        "id",
+        "targets without a week",
+        "SELECT id FROM targets WHERE week_id NOT IN (SELECT id FROM weeks)",
+    ),
+    (
+        "study_sessions",
+        "id",
+        "study sessions without a target",
+        "SELECT id FROM study_sessions WHERE target_id NOT IN (SELECT id FROM targets)",
+    ),
+    (
+        "notes",
+        "id",
+        "notes without an owner",
+        "SELECT id FROM notes
+         WHERE (course_id IS NOT NULL AND course_id NOT IN (SELECT id FROM courses))
+            OR (week_id IS NOT NULL AND week_id NOT IN (SELECT id FROM weeks))
+            OR (target_id IS NOT NULL AND target_id NOT IN (SELECT id FROM targets))",
+    ),
+    (
+        "taggings",
+        "CAST(rowid AS TEXT)",
+        "tags on missing items",
+        "SELECT CAST(rowid AS TEXT) FROM taggings
+         WHERE tag_id NOT IN (SELECT id FROM tags)
+            OR (course_id IS NOT NULL AND course_id NOT IN (SELECT id FROM courses))
+            OR (week_id IS NOT NULL AND week_id NOT IN (SELECT id FROM weeks))
+            OR (target_id IS NOT NULL AND target_id NOT IN (SELECT id FROM targets))",
+    ),
+    (
+        "course_versions",
+        "id",
+        "course versions without a course",
+        "SELECT id FROM course_versions WHERE course_id NOT IN (SELECT id FROM courses)",
+    ),
+    (
+        "term_breaks",
+        "CAST(rowid AS TEXT)",
+        "break weeks without a term",
+        "SELECT CAST(rowid AS TEXT) FROM term_breaks WHERE term_id NOT IN (SELECT id FROM terms)",
+    ),
+];
+
+fn split_ids(ids: String) -> Vec<String> {
+    ids.split(',').map(str::to_string).collect()
+}
+
+async fn collect_issues(tx: &mut Tx<'_>) -> Result<Vec<IntegrityIssue>, sqlx::Error> {
+    let mut issues = Vec::new();
+
+    let rows = sqlx::query(
+        r#"
+        SELECT d.code AS dept_code, c.serial AS serial, GROUP_CONCAT(c.id) AS ids
+        FROM courses c
+        JOIN departments d ON c.department_id = d.id
+        GROUP BY c.department_id, c.serial
+        HAVING COUNT(*) > 1
+        "#
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        let ids = split_ids(row.get("ids"));
+        issues.push(IntegrityIssue {
+            kind: IntegrityIssueKind::DuplicateCourseSerial,
+            message: format!(
+                "{} {} is used by {} courses",
+                row.get::<String, _>("dept_code"),
+                row.get::<i64, _>("serial"),
+                ids.len()
+            ),
+            ids
+        });
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT c.name AS course_name, w.serial AS serial, GROUP_CONCAT(w.id) AS ids
+        FROM weeks w
+        JOIN courses c ON w.course_id = c.id
+        GROUP BY w.course_id, w.serial
+        HAVING COUNT(*) > 1
+        "#
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        let ids = split_ids(row.get("ids"));
+        issues.push(IntegrityIssue {
+            kind: IntegrityIssueKind::DuplicateWeekSerial,
+            message: format!(
+                "{} has {} weeks numbered {}",
+                row.get::<String, _>("course_name"),
+                ids.len(),
+                row.get::<i64, _>("serial")
+            ),
+            ids
+        });
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT c.id AS course_id, c.name AS course_name
+        FROM weeks w
+        JOIN courses c ON w.course_id = c.id
+        GROUP BY w.course_id
+        HAVING MIN(w.serial) != 1
+            OR MAX(w.serial) != COUNT(*)
+            OR COUNT(DISTINCT w.serial) != COUNT(*)
+        "#
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        issues.push(IntegrityIssue {
+            kind: IntegrityIssueKind::WeekSerialGap,
+            message: format!(
+                "Weeks of {} are not numbered 1 to n",
+                row.get::<String, _>("course_name")
+            ),
+            ids: vec![row.get("course_id")]
+        });
+    }
+
+    let ids: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM weeks WHERE date IS NOT NULL AND strftime('%w', date) != '1'"
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    if !ids.is_empty() {
+        issues.push(IntegrityIssue {
+            kind: IntegrityIssueKind::NonMondayWeek,
+            message: format!("{} weeks are dated on a day other than Monday", ids.len()),
+            ids
+        });
+    }
+
+    for (_, _, description, query) in DANGLING_CHECKS {
+        let ids: Vec<String> = sqlx::query_scalar(query)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        if !ids.is_empty() {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::DanglingRow,
+                message: format!("{} {}", ids.len(), description),
+                ids
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+async fn fix_dangling_rows(tx: &mut Tx<'_>) -> Result<(), sqlx::Error> {
+    // Parents come first in the list, so deleting in order also removes rows
+    // that only become dangling because their parent was just deleted.
+    for (table, key, _, query) in DANGLING_CHECKS {
+        let delete = format!("DELETE FROM {} WHERE {} IN ({})", table, key, query);
+        sqlx::query(&delete).execute(&mut **tx).await?;
+    }
+
+    Ok(())
+}
+
+async fn fix_duplicate_course_serials(tx: &mut Tx<'_>) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, department_id, level
+        FROM courses
+        WHERE rowid NOT IN (
+          SELECT MIN(rowid) FROM courses GROUP BY department_id, serial
+        )
+        "#
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        let id: String = row.get("id");
+        let department_id: String = row.get("department_id");
+        let level: Option<CourseLevel> = row.get("level");
+
+        let serial = generate_course_serial(tx, &department_id, level).await?;
+
+        sqlx::query("UPDATE courses SET serial = ? WHERE id = ?")
+            .bind(serial)
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn fix_week_serials(tx: &mut Tx<'_>) -> Result<(), sqlx::Error> {
+    let course_ids: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT course_id
+        FROM weeks
+        GROUP BY course_id
+        HAVING MIN(serial) != 1
+            OR MAX(serial) != COUNT(*)
+            OR COUNT(DISTINCT serial) != COUNT(*)
+        "#
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for course_id in course_ids {
+        let week_ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM weeks WHERE course_id = ? ORDER BY serial, rowid"
+        )
+        .bind(&course_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        // Negative serials keep the unique index happy while renumbering.
+        sqlx::query("UPDATE weeks SET serial = -serial - 1 WHERE course_id = ?")
+            .bind(&course_id)
+            .execute(&mut **tx)
+            .await?;
+
+        for (i, week_id) in week_ids.iter().enumerate() {
+            sqlx::query("UPDATE weeks SET serial = ? WHERE id = ?")
+                .bind(i as i64 + 1)
+                .bind(week_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn fix_non_monday_weeks(tx: &mut Tx<'_>) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, date FROM weeks WHERE date IS NOT NULL AND strftime('%w', date) != '1'"
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        let id: String = row.get("id");
+        let date: NaiveDate = row.get("date");
+        let monday = monday_of(date);
+
+        sqlx::query("UPDATE weeks SET date = ? WHERE id = ?")
+            .bind(monday)
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn check_integrity(
+    pool: &SqlitePool,
+) -> Result<Vec<IntegrityIssue>, Error> {
+    let mut tx = pool.begin().await?;
+
+    let issues = collect_issues(&mut tx).await?;
+
+    tx.rollback().await?;
+
+    Ok(issues)
+}
+
+/// Fixes the requested classes of problems and returns whatever issues
+/// remain afterwards.
+pub async fn repair_integrity(
+    pool: &SqlitePool,
+    kinds: Vec<IntegrityIssueKind>,
+) -> Result<Vec<IntegrityIssue>, Error> {
+    let mut tx = pool.begin().await?;
+
+    if kinds.contains(&IntegrityIssueKind::DanglingRow) {
+        fix_dangling_rows(&mut tx).await?;
+    }
+
+    if kinds.contains(&IntegrityIssueKind::DuplicateCourseSerial) {
+        fix_duplicate_course_serials(&mut tx).await?;
+    }
+
+    if kinds.contains(&IntegrityIssueKind::DuplicateWeekSerial)
+        || kinds.contains(&IntegrityIssueKind::WeekSerialGap)
+    {
+        fix_week_serials(&mut tx).await?;
+    }
+
+    if kinds.contains(&IntegrityIssueKind::NonMondayWeek) {
+        fix_non_monday_weeks(&mut tx).await?;
+    }
+
+    let issues = collect_issues(&mut tx).await?;
+
+    tx.commit().await?;
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory;
+
+    #[tokio::test]
+    async fn dangling_tags_and_versions_are_repaired() {
+        let pool = memory().await.unwrap();
+
+        let orphans = [
+            "PRAGMA foreign_keys = OFF",
+            "INSERT INTO tags (id, name, color) VALUES ('tag', 'exam', '#ff0000')",
+            "INSERT INTO taggings (tag_id, course_id) VALUES ('tag', 'missing')",
+            "INSERT INTO course_versions (id, course_id, origin, content, created_at)
+             VALUES ('version', 'missing', 'manual', '{}', '2026-01-01T00:00:00Z')",
+            "PRAGMA foreign_keys = ON",
+        ];
+        for query in orphans {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+
+        let issues = check_integrity(&pool).await.unwrap();
+        let dangling: Vec<&IntegrityIssue> = issues
+            .iter()
+            .filter(|issue| issue.kind == IntegrityIssueKind::DanglingRow)
+            .collect();
+        assert_eq!(dangling.len(), 2);
+
+        let remaining = repair_integrity(&pool, vec![IntegrityIssueKind::DanglingRow]).await.unwrap();
+        assert!(remaining.is_empty());
+    }
+}
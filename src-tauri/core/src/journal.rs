@@ -0,0 +1,634 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+use crate::departments::revive_departments;
+use crate::error::Error;
+use crate::types::CourseStatus;
+
+type Tx<'a> = sqlx::Transaction<'a, sqlx::Sqlite>;
+
+/// How many operations are kept for undo. Older entries are dropped.
+const JOURNAL_LIMIT: i64 = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TargetSnapshot {
+    id: String,
+    serial: i64,
+    text: String,
+    source: String,
+    is_complete: bool,
+    estimated_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WeekSnapshot {
+    id: String,
+    serial: i64,
+    text: String,
+    date: Option<NaiveDate>,
+    is_complete: bool,
+    targets: Vec<TargetSnapshot>,
+}
+
+/// The editable content of a course as stored, including ids and progress,
+/// so restoring it brings back exactly what was there.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ContentSnapshot {
+    name: String,
+    description: Option<String>,
+    book: Option<String>,
+    weeks: Vec<WeekSnapshot>,
+}
+
+/// A self-contained change that can be replayed against the database.
+/// Every journal entry stores one of these to undo it and one to redo it.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub(crate) enum Operation {
+    TargetStatus {
+        target_id: String,
+        status: bool,
+    },
+    WeekStatus {
+        week_id: String,
+        status: bool,
+    },
+    CourseState {
+        course_id: String,
+        status: CourseStatus,
+        week_dates: Vec<(String, Option<NaiveDate>)>,
+    },
+    Content {
+        course_id: String,
+        content: ContentSnapshot,
+    },
+    CoursePriority {
+        course_id: String,
+        priority: i64,
+    },
+    CourseTerm {
+        course_id: String,
+        term_id: Option<String>,
+    },
+    CourseNumber {
+        course_id: String,
+        department_id: String,
+        serial: i64,
+    },
+    Deleted {
+        course_ids: Vec<String>,
+        deleted_at: Option<DateTime<Utc>>,
+    },
+    DepartmentDeleted {
+        department_id: String,
+        course_ids: Vec<String>,
+        deleted_at: Option<DateTime<Utc>>,
+    },
+    /// Courses added by an import. Undoing one moves the courses to the
+    /// trash rather than deleting them, so redoing it can bring them back.
+    Imported {
+        course_ids: Vec<String>,
+        imported: bool,
+    },
+}
+
+pub(crate) async fn snapshot_content(
+    tx: &mut Tx<'_>,
+    course_id: &str,
+) -> Result<ContentSnapshot, Error> {
+    let row = sqlx::query("SELECT name, description, book FROM courses WHERE id = ?")
+        .bind(course_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let mut content = ContentSnapshot {
+        name: row.get("name"),
+        description: row.get("description"),
+        book: row.get("book"),
+        weeks: Vec::new(),
+    };
+
+    let week_rows = sqlx::query(
+        "SELECT id, serial, text, date, is_complete FROM weeks WHERE course_id = ? ORDER BY serial"
+    )
+    .bind(course_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in week_rows {
+        let week_id: String = row.get("id");
+
+        let targets = sqlx::query(
+            r#"
+            SELECT id, serial, text, source, is_complete, estimated_minutes
+            FROM targets
+            WHERE week_id = ?
+            ORDER BY serial
+            "#
+        )
+        .bind(&week_id)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|row| TargetSnapshot {
+            id: row.get("id"),
+            serial: row.get("serial"),
+            text: row.get("text"),
+            source: row.get("source"),
+            is_complete: row.get("is_complete"),
+            estimated_minutes: row.get("estimated_minutes"),
+        })
+        .collect();
+
+        content.weeks.push(WeekSnapshot {
+            id: week_id,
+            serial: row.get("serial"),
+            text: row.get("text"),
+            date: row.get("date"),
+            is_complete: row.get("is_complete"),
+            targets,
+        });
+    }
+
+    Ok(content)
+}
+
+pub(crate) async fn restore_content(
+    tx: &mut Tx<'_>,
+    course_id: &str,
+    content: &ContentSnapshot,
+) -> Result<(), Error> {
+    sqlx::query("UPDATE courses SET name = ?, description = ?, book = ? WHERE id = ?")
+        .bind(&content.name)
+        .bind(&content.description)
+        .bind(&content.book)
+        .bind(course_id)
+        .execute(&mut **tx)
+        .await?;
+
+    // Rows are matched by id and updated in place, so the sessions, notes and
+    // tags of weeks and targets that exist on both sides are kept.
+    let week_ids: Vec<&str> = content.weeks.iter().map(|w| w.id.as_str()).collect();
+    let target_ids: Vec<&str> = content
+        .weeks
+        .iter()
+        .flat_map(|w| w.targets.iter().map(|t| t.id.as_str()))
+        .collect();
+
+    sqlx::query(
+        r#"
+        DELETE FROM targets
+        WHERE week_id IN (SELECT id FROM weeks WHERE course_id = ?)
+          AND id NOT IN (SELECT value FROM json_each(?))
+        "#
+    )
+    .bind(course_id)
+    .bind(serde_json::to_string(&target_ids)?)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM weeks WHERE course_id = ? AND id NOT IN (SELECT value FROM json_each(?))"
+    )
+    .bind(course_id)
+    .bind(serde_json::to_string(&week_ids)?)
+    .execute(&mut **tx)
+    .await?;
+
+    for week in &content.weeks {
+        sqlx::query(
+            r#"
+            INSERT INTO weeks (id, course_id, serial, text, date, is_complete)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+              serial = excluded.serial,
+              text = excluded.text,
+              date = excluded.date,
+              is_complete = excluded.is_complete
+            "#
+        )
+        .bind(&week.id)
+        .bind(course_id)
+        .bind(week.serial)
+        .bind(&week.text)
+        .bind(week.date)
+        .bind(week.is_complete)
+        .execute(&mut **tx)
+        .await?;
+
+        for target in &week.targets {
+            sqlx::query(
+                r#"
+                INSERT INTO targets
+                (id, week_id, serial, text, source, is_complete, estimated_minutes)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                  week_id = excluded.week_id,
+                  serial = excluded.serial,
+                  text = excluded.text,
+                  source = excluded.source,
+                  is_complete = excluded.is_complete,
+                  estimated_minutes = excluded.estimated_minutes
+                "#
+            )
+            .bind(&target.id)
+            .bind(&week.id)
+            .bind(target.serial)
+            .bind(&target.text)
+            .bind(&target.source)
+            .bind(target.is_complete)
+            .bind(target.estimated_minutes)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn snapshot_status(
+    tx: &mut Tx<'_>,
+    course_id: &str,
+) -> Result<Operation, Error> {
+    let status: CourseStatus = sqlx::query_scalar("SELECT status FROM courses WHERE id = ?")
+        .bind(course_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let week_dates = sqlx::query("SELECT id, date FROM weeks WHERE course_id = ?")
+        .bind(course_id)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|row| (row.get("id"), row.get("date")))
+        .collect();
+
+    Ok(Operation::CourseState {
+        course_id: course_id.to_string(),
+        status,
+        week_dates,
+    })
+}
+
+pub(crate) async fn apply(tx: &mut Tx<'_>, operation: &Operation) -> Result<(), Error> {
+    match operation {
+        Operation::TargetStatus { target_id, status } => {
+            sqlx::query("UPDATE targets SET is_complete = ? WHERE id = ?")
+                .bind(status)
+                .bind(target_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Operation::WeekStatus { week_id, status } => {
+            sqlx::query("UPDATE weeks SET is_complete = ? WHERE id = ?")
+                .bind(status)
+                .bind(week_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Operation::CourseState { course_id, status, week_dates } => {
+            sqlx::query("UPDATE courses SET status = ? WHERE id = ?")
+                .bind(status)
+                .bind(course_id)
+                .execute(&mut **tx)
+                .await?;
+
+            for (week_id, date) in week_dates {
+                sqlx::query("UPDATE weeks SET date = ? WHERE id = ?")
+                    .bind(date)
+                    .bind(week_id)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+        Operation::Content { course_id, content } => {
+            restore_content(tx, course_id, content).await?;
+        }
+        Operation::CoursePriority { course_id, priority } => {
+            sqlx::query("UPDATE courses SET priority = ? WHERE id = ?")
+                .bind(priority)
+                .bind(course_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Operation::CourseTerm { course_id, term_id } => {
+            // The term may have been deleted since, which leaves the course
+            // without one.
+            sqlx::query(
+                "UPDATE courses SET term_id = (SELECT id FROM terms WHERE id = ?) WHERE id = ?"
+            )
+            .bind(term_id)
+            .bind(course_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Operation::CourseNumber { course_id, department_id, serial } => {
+            sqlx::query("UPDATE courses SET department_id = ?, serial = ? WHERE id = ?")
+                .bind(department_id)
+                .bind(serial)
+                .bind(course_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Operation::Deleted { course_ids, deleted_at } => {
+            sqlx::query(
+                "UPDATE courses SET deleted_at = ?
+                 WHERE id IN (SELECT value FROM json_each(?))"
+            )
+            .bind(deleted_at)
+            .bind(serde_json::to_string(course_ids)?)
+            .execute(&mut **tx)
+            .await?;
+
+            if deleted_at.is_none() {
+                revive_departments(tx, course_ids).await?;
+            }
+        }
+        Operation::DepartmentDeleted { department_id, course_ids, deleted_at } => {
+            sqlx::query(
+                "UPDATE courses SET deleted_at = ?
+                 WHERE id IN (SELECT value FROM json_each(?))"
+            )
+            .bind(deleted_at)
+            .bind(serde_json::to_string(course_ids)?)
+            .execute(&mut **tx)
+            .await?;
+
+            sqlx::query("UPDATE departments SET deleted_at = ? WHERE id = ?")
+                .bind(deleted_at)
+                .bind(department_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Operation::Imported { course_ids, imported } => {
+            let deleted_at = (!imported).then(Utc::now);
+
+            sqlx::query(
+                "UPDATE courses SET deleted_at = ?
+                 WHERE id IN (SELECT value FROM json_each(?))"
+            )
+            .bind(deleted_at)
+            .bind(serde_json::to_string(course_ids)?)
+            .execute(&mut **tx)
+            .await?;
+
+            if *imported {
+                revive_departments(tx, course_ids).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Records a change made inside `tx` so it can be undone later. Recording a
+/// new change discards anything that was undone but not redone.
+pub(crate) async fn record(
+    tx: &mut Tx<'_>,
+    label: &str,
+    undo: Operation,
+    redo: Operation,
+) -> Result<(), Error> {
+    sqlx::query("DELETE FROM journal WHERE is_undone = TRUE")
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO journal (label, undo, redo, created_at)
+        VALUES (?, ?, ?, ?)
+        "#
+    )
+    .bind(label)
+    .bind(serde_json::to_string(&undo)?)
+    .bind(serde_json::to_string(&redo)?)
+    .bind(Utc::now())
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query("DELETE FROM journal WHERE seq <= (SELECT MAX(seq) FROM journal) - ?")
+        .bind(JOURNAL_LIMIT)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Drops every journal entry that touches one of `course_ids` or their weeks
+/// and targets. Call it before deleting the courses for good: those entries
+/// could not be applied anymore and would block undo and redo.
+pub(crate) async fn forget_courses(
+    tx: &mut Tx<'_>,
+    course_ids: &[String],
+) -> Result<(), Error> {
+    let ids = serde_json::to_string(course_ids)?;
+
+    sqlx::query(
+        r#"
+        WITH ids AS (
+          SELECT value AS id FROM json_each(?)
+          UNION ALL
+          SELECT w.id FROM weeks w
+          WHERE w.course_id IN (SELECT value FROM json_each(?))
+          UNION ALL
+          SELECT t.id FROM targets t
+          JOIN weeks w ON t.week_id = w.id
+          WHERE w.course_id IN (SELECT value FROM json_each(?))
+        )
+        DELETE FROM journal
+        WHERE EXISTS (
+          SELECT 1 FROM ids
+          WHERE instr(journal.undo, '"' || ids.id || '"') > 0
+             OR instr(journal.redo, '"' || ids.id || '"') > 0
+        )
+        "#
+    )
+    .bind(&ids)
+    .bind(&ids)
+    .bind(&ids)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Drops every journal entry that mentions one of `department_ids`. Call it
+/// before a department goes away, since courses could not be moved back
+/// into it.
+pub(crate) async fn forget_departments(
+    tx: &mut Tx<'_>,
+    department_ids: &[String],
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        DELETE FROM journal
+        WHERE EXISTS (
+          SELECT 1 FROM json_each(?) ids
+          WHERE instr(journal.undo, '"' || ids.value || '"') > 0
+             OR instr(journal.redo, '"' || ids.value || '"') > 0
+        )
+        "#
+    )
+    .bind(serde_json::to_string(department_ids)?)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Reverts the most recent change and returns its label, or `None` when
+/// there is nothing left to undo.
+pub async fn undo(
+    pool: &SqlitePool,
+) -> Result<Option<String>, Error> {
+    let mut tx = pool.begin().await?;
+
+    let entry = sqlx::query(
+        "SELECT seq, label, undo FROM journal WHERE is_undone = FALSE ORDER BY seq DESC LIMIT 1"
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    let operation: Operation = serde_json::from_str(entry.get("undo"))?;
+
+    apply(&mut tx, &operation).await?;
+
+    sqlx::query("UPDATE journal SET is_undone = TRUE WHERE seq = ?")
+        .bind(entry.get::<i64, _>("seq"))
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(entry.get("label")))
+}
+
+/// Re-applies the most recently undone change and returns its label, or
+/// `None` when there is nothing to redo.
+pub async fn redo(
+    pool: &SqlitePool,
+) -> Result<Option<String>, Error> {
+    let mut tx = pool.begin().await?;
+
+    let entry = sqlx::query(
+        "SELECT seq, label, redo FROM journal WHERE is_undone = TRUE ORDER BY seq ASC LIMIT 1"
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    let operation: Operation = serde_json::from_str(entry.get("redo"))?;
+
+    apply(&mut tx, &operation).await?;
+
+    sqlx::query("UPDATE journal SET is_undone = FALSE WHERE seq = ?")
+        .bind(entry.get::<i64, _>("seq"))
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(entry.get("label")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::courses::{delete_course, get_course, update_course};
+    use crate::db::memory;
+    use crate::trash::purge_trash;
+    use crate::types::{Course, CourseContentDraft, TargetDraft, WeekDraft};
+
+    fn content(week: &str, targets: &[&str]) -> CourseContentDraft {
+        CourseContentDraft {
+            name: "Algorithms".to_string(),
+            description: None,
+            book: None,
+            prompt: None,
+            weeks: vec![WeekDraft {
+                serial: 1,
+                text: week.to_string(),
+                targets: targets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, text)| TargetDraft {
+                        serial: i as i64 + 1,
+                        text: text.to_string(),
+                        source: "Book".to_string(),
+                        estimated_minutes: None
+                    })
+                    .collect()
+            }]
+        }
+    }
+
+    #[tokio::test]
+    async fn edits_undo_and_redo() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        update_course(&pool, "algo".to_string(), content("Sorting", &["Read"]), None)
+            .await
+            .unwrap();
+        update_course(&pool, "algo".to_string(), content("Graphs", &["BFS", "DFS"]), None)
+            .await
+            .unwrap();
+
+        let targets = |course: Course| -> Vec<String> {
+            course.weeks.iter().flat_map(|w| w.targets.iter().map(|t| t.text.clone())).collect()
+        };
+
+        assert!(undo(&pool).await.unwrap().is_some());
+        let course = get_course(&pool, "algo".to_string()).await.unwrap();
+        assert_eq!(course.weeks[0].text, "Sorting");
+        assert_eq!(targets(course), vec!["Read"]);
+
+        assert!(redo(&pool).await.unwrap().is_some());
+        let course = get_course(&pool, "algo".to_string()).await.unwrap();
+        assert_eq!(course.weeks[0].text, "Graphs");
+        assert_eq!(targets(course), vec!["BFS", "DFS"]);
+
+        assert_eq!(redo(&pool).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn purging_a_course_clears_its_history() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        update_course(&pool, "algo".to_string(), content("Sorting", &["Read"]), None)
+            .await
+            .unwrap();
+        delete_course(&pool, "algo".to_string()).await.unwrap();
+        purge_trash(&pool, None).await.unwrap();
+
+        assert_eq!(undo(&pool).await.unwrap(), None);
+        assert_eq!(redo(&pool).await.unwrap(), None);
+    }
+}
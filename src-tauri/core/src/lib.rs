@@ -0,0 +1,23 @@
+//! Data access and domain logic for Mnemona, independent of the desktop
+//! shell. Everything here works on a plain `SqlitePool`, so the Tauri
+//! commands, command-line tools and tests can share it.
+
+pub mod courses;
+pub mod db;
+pub mod departments;
+pub mod error;
+pub mod exchange;
+pub mod integrity;
+pub mod journal;
+pub mod notes;
+pub mod paths;
+pub mod schedule;
+pub mod search;
+pub mod sessions;
+pub mod tags;
+pub mod targets;
+pub mod terms;
+pub mod trash;
+pub mod types;
+pub mod versions;
+pub mod weeks;
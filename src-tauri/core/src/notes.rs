@@ -0,0 +1,313 @@
+use chrono::Utc;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::courses::resolve_course;
+use crate::db::fts_query;
+use crate::error::Error;
+use crate::targets::ensure_target;
+use crate::types::{CourseHeader, Note, NoteOwner, NoteSearchHit};
+use crate::weeks::ensure_week;
+
+impl NoteOwner {
+    fn column(&self) -> &'static str {
+        match self {
+            NoteOwner::Course(_) => "course_id",
+            NoteOwner::Week(_) => "week_id",
+            NoteOwner::Target(_) => "target_id"
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            NoteOwner::Course(id) | NoteOwner::Week(id) | NoteOwner::Target(id) => id
+        }
+    }
+
+    /// The same owner, with a course code resolved to the course id. Fails
+    /// with `NotFound` when the owner does not exist.
+    async fn resolve(self, pool: &SqlitePool) -> Result<NoteOwner, Error> {
+        match self {
+            NoteOwner::Course(reference) => Ok(NoteOwner::Course(resolve_course(pool, &reference).await?)),
+            NoteOwner::Week(id) => {
+                ensure_week(pool, &id).await?;
+                Ok(NoteOwner::Week(id))
+            }
+            NoteOwner::Target(id) => {
+                ensure_target(pool, &id).await?;
+                Ok(NoteOwner::Target(id))
+            }
+        }
+    }
+}
+
+fn note_from_row(row: &SqliteRow) -> Note {
+    let owner = if let Some(id) = row.get::<Option<String>, _>("target_id") {
+        NoteOwner::Target(id)
+    } else if let Some(id) = row.get::<Option<String>, _>("week_id") {
+        NoteOwner::Week(id)
+    } else {
+        NoteOwner::Course(row.get("course_id"))
+    };
+
+    Note {
+        id: row.get("id"),
+        owner,
+        body: row.get("body"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at")
+    }
+}
+
+pub async fn create_note(
+    pool: &SqlitePool,
+    owner: NoteOwner,
+    body: String,
+) -> Result<Note, Error> {
+    let owner = owner.resolve(pool).await?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let query = format!(
+        "INSERT INTO notes (id, {}, body, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?)",
+        owner.column()
+    );
+
+    sqlx::query(&query)
+        .bind(&id)
+        .bind(owner.id())
+        .bind(&body)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+    Ok(Note {
+        id,
+        owner,
+        body,
+        created_at: now,
+        updated_at: now
+    })
+}
+
+pub async fn get_notes(
+    pool: &SqlitePool,
+    owner: NoteOwner,
+) -> Result<Vec<Note>, Error> {
+    let owner = owner.resolve(pool).await?;
+    let query = format!(
+        r#"
+        SELECT id, course_id, week_id, target_id, body, created_at, updated_at
+        FROM notes
+        WHERE {} = ?
+        ORDER BY created_at
+        "#,
+        owner.column()
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(owner.id())
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(note_from_row).collect())
+}
+
+pub async fn update_note(
+    pool: &SqlitePool,
+    note_id: String,
+    body: String,
+) -> Result<(), Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE notes
+        SET body = ?, updated_at = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(body)
+    .bind(Utc::now())
+    .bind(&note_id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Note not found: {}", note_id)));
+    }
+
+    Ok(())
+}
+
+pub async fn delete_note(
+    pool: &SqlitePool,
+    note_id: String,
+) -> Result<(), Error> {
+    let result = sqlx::query("DELETE FROM notes WHERE id = ?")
+        .bind(&note_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Note not found: {}", note_id)));
+    }
+
+    Ok(())
+}
+
+pub async fn search_notes(
+    pool: &SqlitePool,
+    query: String,
+) -> Result<Vec<NoteSearchHit>, Error> {
+    let Some(query) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          n.id,
+          n.course_id,
+          n.week_id,
+          n.target_id,
+          n.body,
+          n.created_at,
+          n.updated_at,
+          snippet(notes_fts, 0, '<mark>', '</mark>', '…', 16) AS snippet,
+
+          w.serial        AS week_serial,
+
+          c.id            AS course_id_ctx,
+          c.serial        AS course_serial,
+          c.name          AS course_name,
+          c.status        AS course_status,
+
+          d.code          AS dept_code
+        FROM notes_fts
+        JOIN notes n ON n.rowid = notes_fts.rowid
+        LEFT JOIN targets t ON t.id = n.target_id
+        LEFT JOIN weeks w ON w.id = COALESCE(n.week_id, t.week_id)
+        JOIN courses c ON c.id = COALESCE(n.course_id, w.course_id)
+        JOIN departments d ON c.department_id = d.id
+        WHERE notes_fts MATCH ?
+          AND c.deleted_at IS NULL
+        ORDER BY rank
+        "#
+    )
+    .bind(query)
+    .fetch_all(pool)
+    .await?;
+
+    let hits = rows
+        .iter()
+        .map(|row| NoteSearchHit {
+            note: note_from_row(row),
+            snippet: row.get("snippet"),
+            course: CourseHeader {
+                id: row.get("course_id_ctx"),
+                department: row.get("dept_code"),
+                serial: row.get("course_serial"),
+                name: row.get("course_name"),
+                status: row.get("course_status")
+            },
+            week_serial: row.get("week_serial")
+        })
+        .collect();
+
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::courses::update_course;
+    use crate::db::memory;
+    use crate::journal::{redo, undo};
+    use crate::types::{CourseContentDraft, TargetDraft, WeekDraft};
+
+    #[tokio::test]
+    async fn notes_survive_edits_and_undo() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            INSERT INTO weeks (id, course_id, serial, text) VALUES ('sorting', 'algo', 1, 'Sorting');
+            INSERT INTO targets (id, week_id, serial, text, source) VALUES ('read', 'sorting', 1, 'Read', 'Book');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let week = NoteOwner::Week("sorting".to_string());
+        let target = NoteOwner::Target("read".to_string());
+
+        create_note(&pool, week.clone(), "Week note".to_string()).await.unwrap();
+        create_note(&pool, target.clone(), "Target note".to_string()).await.unwrap();
+
+        let draft = CourseContentDraft {
+            name: "Algorithms".to_string(),
+            description: None,
+            book: None,
+            prompt: None,
+            weeks: vec![WeekDraft {
+                serial: 1,
+                text: "Sorting".to_string(),
+                targets: vec![TargetDraft {
+                    serial: 1,
+                    text: "Read again".to_string(),
+                    source: "Book".to_string(),
+                    estimated_minutes: None
+                }]
+            }]
+        };
+        update_course(&pool, "algo".to_string(), draft, None).await.unwrap();
+        undo(&pool).await.unwrap();
+        redo(&pool).await.unwrap();
+
+        assert_eq!(get_notes(&pool, week).await.unwrap().len(), 1);
+        assert_eq!(get_notes(&pool, target).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn course_notes_accept_course_codes() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let note = create_note(&pool, NoteOwner::Course("CS 241".to_string()), "Hi".to_string())
+            .await
+            .unwrap();
+
+        assert!(matches!(note.owner, NoteOwner::Course(id) if id == "algo"));
+        assert_eq!(get_notes(&pool, NoteOwner::Course("CS-241".to_string())).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn missing_notes_and_owners_are_not_found() {
+        let pool = memory().await.unwrap();
+
+        let updated = update_note(&pool, "missing".to_string(), "Body".to_string()).await;
+        assert!(matches!(updated, Err(Error::NotFound(_))));
+
+        let deleted = delete_note(&pool, "missing".to_string()).await;
+        assert!(matches!(deleted, Err(Error::NotFound(_))));
+
+        for owner in [NoteOwner::Week("missing".to_string()), NoteOwner::Target("missing".to_string())] {
+            let created = create_note(&pool, owner, "Body".to_string()).await;
+            assert!(matches!(created, Err(Error::NotFound(_))));
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Where Mnemona keeps its files. The app and the command-line tools both
+//! resolve paths here, so they always find the same library.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::types::{DataDirectory, DataDirectorySource};
+
+/// The app's bundle identifier, which names its folder in the platform data
+/// dir.
+pub const IDENTIFIER: &str = "com.glicomak.mnemona";
+pub const ENV_VAR: &str = "MNEMONA_DATA_DIR";
+pub const DATABASE_FILE: &str = "mnemona.db";
+const PORTABLE_MARKER: &str = "portable";
+
+/// Everything stored at the root of the data directory.
+pub const ROOT_ENTRIES: [&str; 2] = ["profiles.json", "profiles"];
+
+/// Written to the platform data dir by `set_pointer` to point at the
+/// directory the data now lives in.
+#[derive(Serialize, Deserialize)]
+struct Pointer {
+    data_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The list of profiles and which one is open, stored in `profiles.json` at
+/// the root of the data directory. Each profile keeps its database, settings
+/// and backups in `profiles/<id>`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Registry {
+    pub active: String,
+    pub profiles: Vec<ProfileEntry>,
+}
+
+/// The platform data dir, where data lives unless told otherwise. The app
+/// gets the same directory from Tauri.
+pub fn default_dir() -> Result<PathBuf, Error> {
+    dirs::data_dir()
+        .map(|dir| dir.join(IDENTIFIER))
+        .ok_or_else(|| Error::NotFound("Could not find the platform data directory".to_string()))
+}
+
+fn pointer_path(default_dir: &Path) -> PathBuf {
+    default_dir.join("location.json")
+}
+
+fn from_env() -> Option<PathBuf> {
+    std::env::var_os(ENV_VAR)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// A `portable` file next to the executable keeps data in a `data` folder
+/// beside it, so the whole app can live on a removable drive.
+fn from_portable_marker() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+
+    dir.join(PORTABLE_MARKER)
+        .exists()
+        .then(|| dir.join("data"))
+}
+
+fn from_pointer(default_dir: &Path) -> Result<Option<PathBuf>, Error> {
+    let path = pointer_path(default_dir);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let pointer: Pointer = serde_json::from_str(&contents)?;
+
+    Ok(Some(pointer.data_dir))
+}
+
+/// Where all profiles are stored, in order of precedence: `flag`, taken from
+/// the command line, the environment variable, the portable marker, a
+/// directory set with `set_pointer`, and finally `default_dir`.
+pub fn resolve(flag: Option<PathBuf>, default_dir: &Path) -> Result<DataDirectory, Error> {
+    let (path, source) = if let Some(path) = flag {
+        (path, DataDirectorySource::Flag)
+    } else if let Some(path) = from_env() {
+        (path, DataDirectorySource::Env)
+    } else if let Some(path) = from_portable_marker() {
+        (path, DataDirectorySource::Portable)
+    } else if let Some(path) = from_pointer(default_dir)? {
+        (path, DataDirectorySource::Moved)
+    } else {
+        (default_dir.to_path_buf(), DataDirectorySource::Default)
+    };
+
+    Ok(DataDirectory { path, source })
+}
+
+/// Records `data_dir` as the new home of the data, or forgets the override
+/// when it is `default_dir`.
+pub fn set_pointer(default_dir: &Path, data_dir: &Path) -> Result<(), Error> {
+    let path = pointer_path(default_dir);
+
+    if data_dir == default_dir {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(default_dir)?;
+
+    let pointer = Pointer { data_dir: data_dir.to_path_buf() };
+    let contents = serde_json::to_string_pretty(&pointer)?;
+    fs::write(path, contents).map_err(Error::from)
+}
+
+fn registry_path(root: &Path) -> PathBuf {
+    root.join("profiles.json")
+}
+
+/// The registry under `root`, or `None` for a library from before profiles
+/// existed.
+pub fn read_registry(root: &Path) -> Result<Option<Registry>, Error> {
+    let path = registry_path(root);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+pub fn write_registry(root: &Path, registry: &Registry) -> Result<(), Error> {
+    let contents = serde_json::to_string_pretty(registry)?;
+    fs::write(registry_path(root), contents).map_err(Error::from)
+}
+
+pub fn profile_dir(root: &Path, profile_id: &str) -> PathBuf {
+    root.join("profiles").join(profile_id)
+}
+
+/// The directory of `profile`, matched by id or case-insensitive name, or of
+/// the active profile without one. A library from before profiles existed
+/// has no registry and is used where it is.
+pub fn find_profile_dir(root: &Path, profile: Option<&str>) -> Result<PathBuf, Error> {
+    let Some(registry) = read_registry(root)? else {
+        return match profile {
+            None => Ok(root.to_path_buf()),
+            Some(profile) => Err(Error::NotFound(format!("No profile named {}", profile))),
+        };
+    };
+
+    let id = match profile {
+        None => registry.active,
+        Some(profile) => registry
+            .profiles
+            .into_iter()
+            .find(|p| p.id == profile || p.name.eq_ignore_ascii_case(profile))
+            .map(|p| p.id)
+            .ok_or_else(|| Error::NotFound(format!("No profile named {}", profile)))?,
+    };
+
+    Ok(profile_dir(root, &id))
+}
+
+pub fn database_path(profile_dir: &Path) -> PathBuf {
+    profile_dir.join(DATABASE_FILE)
+}
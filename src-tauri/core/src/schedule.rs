@@ -0,0 +1,168 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use sqlx::{Row, SqlitePool};
+
+use crate::{error::Error, types::{CourseHeader, ScheduleItem, Target, Week}};
+
+/// The Monday of the week containing `date`. Weeks are scheduled and stored
+/// by their Monday.
+pub fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// The weeks scheduled for the Monday `date`, grouped by course, with
+/// higher-priority courses first. With `tags`, only targets carrying one of
+/// them, directly or through their week or course, are kept, along with the
+/// weeks that still have any.
+pub async fn get_schedule(
+    pool: &SqlitePool,
+    date: NaiveDate,
+    tags: Vec<String>,
+) -> Result<Vec<ScheduleItem>, Error> {
+    let tags = serde_json::to_string(&tags)?;
+
+    let week_rows = sqlx::query(
+        r#"
+        SELECT
+          w.id            AS week_id,
+          w.serial        AS week_serial,
+          w.text          AS week_text,
+          w.date          AS week_date,
+          w.is_complete   AS week_complete,
+
+          c.id            AS course_id,
+          c.serial        AS course_serial,
+          c.name          AS course_name,
+          c.status        AS course_status,
+
+          d.code          AS dept_code
+        FROM weeks w
+        JOIN courses c ON w.course_id = c.id
+        JOIN departments d ON c.department_id = d.id
+        WHERE w.date = ?
+          AND c.deleted_at IS NULL
+          AND (
+            c.status IN ('active', 'complete')
+            OR (c.status = 'inactive' AND w.is_complete = true)
+          )
+          AND (json_array_length(?) = 0 OR EXISTS (
+            SELECT 1
+            FROM taggings g
+            JOIN tags tg ON g.tag_id = tg.id
+            LEFT JOIN targets gt ON gt.id = g.target_id
+            WHERE tg.name IN (SELECT value FROM json_each(?))
+              AND (g.course_id = c.id OR g.week_id = w.id OR gt.week_id = w.id)
+          ))
+        ORDER BY c.priority DESC, d.code, c.serial, w.serial
+        "#
+    )
+    .bind(date)
+    .bind(&tags)
+    .bind(&tags)
+    .fetch_all(pool)
+    .await?;
+
+    let week_ids: Vec<String> = week_rows
+        .iter()
+        .map(|r| r.get::<String, _>("week_id"))
+        .collect();
+
+    let target_rows = sqlx::query(
+        r#"
+        SELECT
+          t.id,
+          t.week_id,
+          t.serial,
+          t.text,
+          t.source,
+          t.is_complete,
+          t.estimated_minutes,
+          COALESCE(SUM(s.duration_seconds), 0) AS seconds_spent
+        FROM targets t
+        LEFT JOIN study_sessions s ON s.target_id = t.id
+        WHERE t.week_id IN (
+          SELECT value FROM json_each(?)
+        )
+          AND (json_array_length(?) = 0 OR EXISTS (
+            SELECT 1
+            FROM taggings g
+            JOIN tags tg ON g.tag_id = tg.id
+            WHERE tg.name IN (SELECT value FROM json_each(?))
+              AND (
+                g.target_id = t.id
+                OR g.week_id = t.week_id
+                OR g.course_id = (SELECT course_id FROM weeks WHERE id = t.week_id)
+              )
+          ))
+        GROUP BY t.id
+        ORDER BY t.week_id, t.serial
+        "#
+    )
+    .bind(serde_json::to_string(&week_ids)?)
+    .bind(&tags)
+    .bind(&tags)
+    .fetch_all(pool)
+    .await?;
+
+    use std::collections::HashMap;
+
+    let mut targets_by_week: HashMap<String, Vec<Target>> = HashMap::new();
+
+    for row in target_rows {
+        let week_id: String = row.get("week_id");
+
+        targets_by_week
+            .entry(week_id)
+            .or_default()
+            .push(Target {
+                id: row.get("id"),
+                serial: row.get("serial"),
+                text: row.get("text"),
+                source: row.get("source"),
+                is_complete: row.get("is_complete"),
+                estimated_minutes: row.get("estimated_minutes"),
+                seconds_spent: row.get("seconds_spent"),
+            });
+    }
+
+    let mut schedule: Vec<ScheduleItem> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for row in week_rows {
+        let course_id: String = row.get("course_id");
+        let week_id: String = row.get("week_id");
+
+        let targets = targets_by_week.remove(&week_id).unwrap_or_default();
+
+        let week = Week {
+            id: week_id.clone(),
+            serial: row.get("week_serial"),
+            text: row.get("week_text"),
+            date: row.get::<Option<NaiveDate>, _>("week_date"),
+            is_complete: row.get("week_complete"),
+            estimated_minutes: targets
+                .iter()
+                .filter_map(|t| t.estimated_minutes)
+                .reduce(|a, b| a + b),
+            seconds_spent: targets.iter().map(|t| t.seconds_spent).sum(),
+            targets,
+        };
+
+        let position = *positions.entry(course_id.clone()).or_insert_with(|| {
+            schedule.push(ScheduleItem {
+                course: CourseHeader {
+                    id: course_id.clone(),
+                    department: row.get("dept_code"),
+                    serial: row.get("course_serial"),
+                    name: row.get("course_name"),
+                    status: row.get("course_status"),
+                },
+                weeks: Vec::new(),
+            });
+            schedule.len() - 1
+        });
+
+        schedule[position].weeks.push(week);
+    }
+
+    Ok(schedule)
+}
@@ -0,0 +1,87 @@
+use sqlx::{Row, SqlitePool};
+
+use crate::db::fts_query;
+use crate::error::Error;
+use crate::types::{CourseHeader, SearchFilters, SearchHit};
+
+/// Finds courses, weeks and targets matching `query`. A tag filter keeps
+/// hits tagged with one of the tags or inside a week or course that is.
+pub async fn search(
+    pool: &SqlitePool,
+    query: String,
+    filters: SearchFilters,
+) -> Result<Vec<SearchHit>, Error> {
+    let Some(query) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+
+    let departments = serde_json::to_string(&filters.departments)?;
+    let statuses = serde_json::to_string(&filters.statuses)?;
+    let tags = serde_json::to_string(&filters.tags)?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          s.kind          AS kind,
+          s.item_id       AS item_id,
+          snippet(search_index, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+
+          w.serial        AS week_serial,
+
+          c.id            AS course_id,
+          c.serial        AS course_serial,
+          c.name          AS course_name,
+          c.status        AS course_status,
+
+          d.code          AS dept_code
+        FROM search_index s
+        JOIN courses c ON c.id = s.course_id
+        JOIN departments d ON c.department_id = d.id
+        LEFT JOIN targets t ON s.kind = 'target' AND t.id = s.item_id
+        LEFT JOIN weeks w ON w.id = CASE s.kind
+            WHEN 'week' THEN s.item_id
+            WHEN 'target' THEN t.week_id
+          END
+        WHERE search_index MATCH ?
+          AND c.deleted_at IS NULL
+          AND (json_array_length(?) = 0 OR d.code IN (SELECT value FROM json_each(?)))
+          AND (json_array_length(?) = 0 OR c.status IN (SELECT value FROM json_each(?)))
+          AND (json_array_length(?) = 0 OR EXISTS (
+            SELECT 1
+            FROM taggings g
+            JOIN tags tg ON g.tag_id = tg.id
+            WHERE tg.name IN (SELECT value FROM json_each(?))
+              AND (g.course_id = c.id OR g.week_id = w.id OR g.target_id = t.id)
+          ))
+        ORDER BY rank
+        "#
+    )
+    .bind(query)
+    .bind(&departments)
+    .bind(&departments)
+    .bind(&statuses)
+    .bind(&statuses)
+    .bind(&tags)
+    .bind(&tags)
+    .fetch_all(pool)
+    .await?;
+
+    let hits = rows
+        .into_iter()
+        .map(|row| SearchHit {
+            kind: row.get("kind"),
+            id: row.get("item_id"),
+            course: CourseHeader {
+                id: row.get("course_id"),
+                department: row.get("dept_code"),
+                serial: row.get("course_serial"),
+                name: row.get("course_name"),
+                status: row.get("course_status")
+            },
+            week_serial: row.get("week_serial"),
+            highlight: row.get("snippet")
+        })
+        .collect();
+
+    Ok(hits)
+}
@@ -0,0 +1,205 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::targets::ensure_target;
+use crate::types::StudySession;
+
+/// The longest session that can be logged after the fact.
+const MAX_LOGGED_MINUTES: i64 = 24 * 60;
+
+async fn close_open_sessions(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, started_at FROM study_sessions WHERE ended_at IS NULL"
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for row in rows {
+        let id: String = row.get("id");
+        let started_at: DateTime<Utc> = row.get("started_at");
+        let duration = (now - started_at).num_seconds().max(0);
+
+        sqlx::query(
+            r#"
+            UPDATE study_sessions
+            SET ended_at = ?, duration_seconds = ?
+            WHERE id = ?
+            "#
+        )
+        .bind(now)
+        .bind(duration)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn start_session(
+    pool: &SqlitePool,
+    target_id: String,
+) -> Result<StudySession, Error> {
+    ensure_target(pool, &target_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let now = Utc::now();
+
+    close_open_sessions(&mut tx, now).await?;
+
+    let id = Uuid::new_v4().to_string();
+
+    sqlx::query(
+        r#"
+        INSERT INTO study_sessions (id, target_id, started_at)
+        VALUES (?, ?, ?)
+        "#
+    )
+    .bind(&id)
+    .bind(&target_id)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StudySession {
+        id,
+        target_id,
+        started_at: now,
+        ended_at: None,
+        duration_seconds: None
+    })
+}
+
+pub async fn stop_session(
+    pool: &SqlitePool,
+    session_id: String,
+) -> Result<StudySession, Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT target_id, started_at, ended_at, duration_seconds
+        FROM study_sessions
+        WHERE id = ?
+        "#
+    )
+    .bind(&session_id)
+    .fetch_one(pool)
+    .await?;
+
+    let mut session = StudySession {
+        id: session_id,
+        target_id: row.get("target_id"),
+        started_at: row.get("started_at"),
+        ended_at: row.get("ended_at"),
+        duration_seconds: row.get("duration_seconds")
+    };
+
+    if session.ended_at.is_some() {
+        return Ok(session);
+    }
+
+    let now = Utc::now();
+    let duration = (now - session.started_at).num_seconds().max(0);
+
+    sqlx::query(
+        r#"
+        UPDATE study_sessions
+        SET ended_at = ?, duration_seconds = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(now)
+    .bind(duration)
+    .bind(&session.id)
+    .execute(pool)
+    .await?;
+
+    session.ended_at = Some(now);
+    session.duration_seconds = Some(duration);
+
+    Ok(session)
+}
+
+pub async fn log_session(
+    pool: &SqlitePool,
+    target_id: String,
+    started_at: DateTime<Utc>,
+    minutes: i64,
+) -> Result<StudySession, Error> {
+    if minutes <= 0 {
+        return Err(Error::Validation("session length must be positive".to_string()));
+    }
+
+    if minutes > MAX_LOGGED_MINUTES {
+        return Err(Error::Validation(format!(
+            "session length cannot exceed {} minutes",
+            MAX_LOGGED_MINUTES
+        )));
+    }
+
+    ensure_target(pool, &target_id).await?;
+
+    let out_of_range = || Error::Validation("session time is out of range".to_string());
+
+    let id = Uuid::new_v4().to_string();
+    let ended_at = Duration::try_minutes(minutes)
+        .and_then(|length| started_at.checked_add_signed(length))
+        .ok_or_else(out_of_range)?;
+    let duration = minutes.checked_mul(60).ok_or_else(out_of_range)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO study_sessions (id, target_id, started_at, ended_at, duration_seconds)
+        VALUES (?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&id)
+    .bind(&target_id)
+    .bind(started_at)
+    .bind(ended_at)
+    .bind(duration)
+    .execute(pool)
+    .await?;
+
+    Ok(StudySession {
+        id,
+        target_id,
+        started_at,
+        ended_at: Some(ended_at),
+        duration_seconds: Some(duration)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory;
+
+    #[tokio::test]
+    async fn overlong_sessions_are_rejected() {
+        let pool = memory().await.unwrap();
+
+        for minutes in [MAX_LOGGED_MINUTES + 1, i64::MAX] {
+            let logged = log_session(&pool, "target".to_string(), Utc::now(), minutes).await;
+            assert!(matches!(logged, Err(Error::Validation(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn sessions_need_an_existing_target() {
+        let pool = memory().await.unwrap();
+
+        let started = start_session(&pool, "missing".to_string()).await;
+        assert!(matches!(started, Err(Error::NotFound(_))));
+
+        let logged = log_session(&pool, "missing".to_string(), Utc::now(), 30).await;
+        assert!(matches!(logged, Err(Error::NotFound(_))));
+    }
+}
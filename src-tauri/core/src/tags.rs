@@ -0,0 +1,312 @@
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::courses::resolve_course;
+use crate::error::Error;
+use crate::targets::ensure_target;
+use crate::types::{Tag, TagDraft, TaggedItem};
+use crate::weeks::ensure_week;
+
+impl TaggedItem {
+    fn column(&self) -> &'static str {
+        match self {
+            TaggedItem::Course(_) => "course_id",
+            TaggedItem::Week(_) => "week_id",
+            TaggedItem::Target(_) => "target_id"
+        }
+    }
+
+    /// The id of the item, with course codes resolved to course ids. Fails
+    /// with `NotFound` when the item does not exist.
+    async fn resolve(&self, pool: &SqlitePool) -> Result<String, Error> {
+        match self {
+            TaggedItem::Course(reference) => resolve_course(pool, reference).await,
+            TaggedItem::Week(id) => ensure_week(pool, id).await.map(|_| id.clone()),
+            TaggedItem::Target(id) => ensure_target(pool, id).await.map(|_| id.clone())
+        }
+    }
+}
+
+fn validate_tag(tag: &TagDraft) -> Result<(), Error> {
+    if tag.name.trim().is_empty() {
+        return Err(Error::Validation("Tag name cannot be empty".to_string()));
+    }
+
+    let is_hex_color = tag.color.len() == 7
+        && tag.color.starts_with('#')
+        && tag.color[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+    if !is_hex_color {
+        return Err(Error::Validation(format!("Invalid tag color: {}", tag.color)));
+    }
+
+    Ok(())
+}
+
+async fn ensure_name_free(
+    pool: &SqlitePool,
+    name: &str,
+    except_id: Option<&str>,
+) -> Result<(), Error> {
+    let existing: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM tags WHERE name = ?"
+    )
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    match existing {
+        Some(id) if Some(id.as_str()) != except_id => {
+            Err(Error::Conflict(format!("Tag name already in use: {}", name)))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub async fn get_tags(
+    pool: &SqlitePool,
+) -> Result<Vec<Tag>, Error> {
+    let rows = sqlx::query("SELECT id, name, color FROM tags ORDER BY name")
+        .fetch_all(pool)
+        .await?;
+
+    let tags = rows
+        .into_iter()
+        .map(|row| Tag {
+            id: row.get("id"),
+            name: row.get("name"),
+            color: row.get("color")
+        })
+        .collect();
+
+    Ok(tags)
+}
+
+/// The tags attached directly to `item`, without those it inherits from the
+/// week or course it belongs to.
+pub async fn get_item_tags(
+    pool: &SqlitePool,
+    item: TaggedItem,
+) -> Result<Vec<Tag>, Error> {
+    let item_id = item.resolve(pool).await?;
+
+    let query = format!(
+        r#"
+        SELECT t.id, t.name, t.color
+        FROM tags t
+        JOIN taggings g ON g.tag_id = t.id
+        WHERE g.{} = ?
+        ORDER BY t.name
+        "#,
+        item.column()
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(item_id)
+        .fetch_all(pool)
+        .await?;
+
+    let tags = rows
+        .into_iter()
+        .map(|row| Tag {
+            id: row.get("id"),
+            name: row.get("name"),
+            color: row.get("color")
+        })
+        .collect();
+
+    Ok(tags)
+}
+
+pub async fn create_tag(
+    pool: &SqlitePool,
+    tag: TagDraft,
+) -> Result<Tag, Error> {
+    validate_tag(&tag)?;
+    ensure_name_free(pool, tag.name.trim(), None).await?;
+
+    let tag = Tag {
+        id: Uuid::new_v4().to_string(),
+        name: tag.name.trim().to_string(),
+        color: tag.color.to_lowercase()
+    };
+
+    sqlx::query("INSERT INTO tags (id, name, color) VALUES (?, ?, ?)")
+        .bind(&tag.id)
+        .bind(&tag.name)
+        .bind(&tag.color)
+        .execute(pool)
+        .await?;
+
+    Ok(tag)
+}
+
+pub async fn update_tag(
+    pool: &SqlitePool,
+    tag_id: String,
+    tag: TagDraft,
+) -> Result<(), Error> {
+    validate_tag(&tag)?;
+    ensure_name_free(pool, tag.name.trim(), Some(&tag_id)).await?;
+
+    let result = sqlx::query("UPDATE tags SET name = ?, color = ? WHERE id = ?")
+        .bind(tag.name.trim())
+        .bind(tag.color.to_lowercase())
+        .bind(&tag_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Tag not found: {}", tag_id)));
+    }
+
+    Ok(())
+}
+
+/// Deletes a tag and removes it from everything it was attached to.
+pub async fn delete_tag(
+    pool: &SqlitePool,
+    tag_id: String,
+) -> Result<(), Error> {
+    let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(&tag_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Tag not found: {}", tag_id)));
+    }
+
+    Ok(())
+}
+
+/// Attaches a tag to a course, week or target. Attaching a tag that is
+/// already there does nothing.
+pub async fn attach_tag(
+    pool: &SqlitePool,
+    tag_id: String,
+    item: TaggedItem,
+) -> Result<(), Error> {
+    let item_id = item.resolve(pool).await?;
+
+    let tag_exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM tags WHERE id = ?")
+        .bind(&tag_id)
+        .fetch_optional(pool)
+        .await?;
+
+    if tag_exists.is_none() {
+        return Err(Error::NotFound(format!("Tag not found: {}", tag_id)));
+    }
+
+    let query = format!(
+        "INSERT OR IGNORE INTO taggings (tag_id, {}) VALUES (?, ?)",
+        item.column()
+    );
+
+    sqlx::query(&query)
+        .bind(tag_id)
+        .bind(item_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn detach_tag(
+    pool: &SqlitePool,
+    tag_id: String,
+    item: TaggedItem,
+) -> Result<(), Error> {
+    let item_id = item.resolve(pool).await?;
+
+    let query = format!(
+        "DELETE FROM taggings WHERE tag_id = ? AND {} = ?",
+        item.column()
+    );
+
+    sqlx::query(&query)
+        .bind(tag_id)
+        .bind(item_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::courses::update_course;
+    use crate::db::memory;
+    use crate::journal::undo;
+    use crate::types::{CourseContentDraft, TargetDraft, WeekDraft};
+    use crate::versions::{list_course_versions, revert_course_version};
+
+    #[tokio::test]
+    async fn tags_survive_edits_reverts_and_undo() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            INSERT INTO weeks (id, course_id, serial, text) VALUES ('sorting', 'algo', 1, 'Sorting');
+            INSERT INTO targets (id, week_id, serial, text, source) VALUES ('read', 'sorting', 1, 'Read', 'Book');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let week = TaggedItem::Week("sorting".to_string());
+        let target = TaggedItem::Target("read".to_string());
+
+        let tag = create_tag(&pool, TagDraft { name: "exam".to_string(), color: "#FF0000".to_string() })
+            .await
+            .unwrap();
+        attach_tag(&pool, tag.id.clone(), week.clone()).await.unwrap();
+        attach_tag(&pool, tag.id.clone(), target.clone()).await.unwrap();
+
+        let draft = CourseContentDraft {
+            name: "Algorithms".to_string(),
+            description: None,
+            book: None,
+            prompt: None,
+            weeks: vec![WeekDraft {
+                serial: 1,
+                text: "Sorting".to_string(),
+                targets: vec![TargetDraft {
+                    serial: 1,
+                    text: "Read twice".to_string(),
+                    source: "Book".to_string(),
+                    estimated_minutes: None
+                }]
+            }]
+        };
+        update_course(&pool, "algo".to_string(), draft, None).await.unwrap();
+
+        let versions = list_course_versions(&pool, "algo".to_string()).await.unwrap();
+        revert_course_version(&pool, versions.last().unwrap().id.clone()).await.unwrap();
+        undo(&pool).await.unwrap();
+
+        assert_eq!(get_item_tags(&pool, week).await.unwrap().len(), 1);
+        assert_eq!(get_item_tags(&pool, target).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn tagging_missing_items_is_not_found() {
+        let pool = memory().await.unwrap();
+
+        let tag = create_tag(&pool, TagDraft { name: "exam".to_string(), color: "#FF0000".to_string() })
+            .await
+            .unwrap();
+
+        for item in [TaggedItem::Week("missing".to_string()), TaggedItem::Target("missing".to_string())] {
+            let attached = attach_tag(&pool, tag.id.clone(), item).await;
+            assert!(matches!(attached, Err(Error::NotFound(_))));
+        }
+
+        let deleted = delete_tag(&pool, "missing".to_string()).await;
+        assert!(matches!(deleted, Err(Error::NotFound(_))));
+    }
+}
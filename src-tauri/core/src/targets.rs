@@ -0,0 +1,89 @@
+use sqlx::SqlitePool;
+
+use crate::journal::{record, Operation};
+use crate::error::Error;
+
+/// Fails with `NotFound` unless `target_id` is a target of a course that is
+/// not in the trash.
+pub async fn ensure_target(
+    pool: &SqlitePool,
+    target_id: &str,
+) -> Result<(), Error> {
+    let exists: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT 1
+        FROM targets t
+        JOIN weeks w ON t.week_id = w.id
+        JOIN courses c ON w.course_id = c.id
+        WHERE t.id = ? AND c.deleted_at IS NULL
+        "#
+    )
+    .bind(target_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound(format!("Target not found: {}", target_id))),
+    }
+}
+
+/// Finds target `target_serial` of week `week_serial` in a course.
+pub async fn find_target_id(
+    pool: &SqlitePool,
+    course_id: &str,
+    week_serial: i64,
+    target_serial: i64,
+) -> Result<String, Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT t.id
+        FROM targets t
+        JOIN weeks w ON t.week_id = w.id
+        WHERE w.course_id = ? AND w.serial = ? AND t.serial = ?
+        "#
+    )
+    .bind(course_id)
+    .bind(week_serial)
+    .bind(target_serial)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("No target {}.{} in this course", week_serial, target_serial)))
+}
+
+pub async fn change_target_status(
+    pool: &SqlitePool,
+    target_id: String,
+    status: bool,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous: bool = sqlx::query_scalar("SELECT is_complete FROM targets WHERE id = ?")
+        .bind(&target_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE targets
+        SET is_complete = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(status)
+    .bind(&target_id)
+    .execute(&mut *tx)
+    .await?;
+
+    record(
+        &mut tx,
+        "Change target status",
+        Operation::TargetStatus { target_id: target_id.clone(), status: previous },
+        Operation::TargetStatus { target_id, status },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
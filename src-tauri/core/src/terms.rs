@@ -0,0 +1,248 @@
+use chrono::{Local, NaiveDate};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::courses::get_courses;
+use crate::error::Error;
+use crate::schedule::monday_of;
+use crate::types::{CourseQuery, Term, TermDraft, TermOverview};
+
+impl Term {
+    /// The Mondays of the weeks in the term that are not breaks.
+    pub fn study_weeks(&self) -> Vec<NaiveDate> {
+        monday_of(self.start_date)
+            .iter_weeks()
+            .take_while(|monday| *monday <= self.end_date)
+            .filter(|monday| !self.break_weeks.contains(monday))
+            .collect()
+    }
+}
+
+/// Checks `term` and returns its break weeks as sorted, distinct Mondays.
+fn validate_term(term: &TermDraft) -> Result<Vec<NaiveDate>, Error> {
+    if term.name.trim().is_empty() {
+        return Err(Error::Validation("Term name cannot be empty".to_string()));
+    }
+
+    if term.start_date > term.end_date {
+        return Err(Error::Validation("A term cannot end before it starts".to_string()));
+    }
+
+    let mut break_weeks: Vec<NaiveDate> = term.break_weeks.iter().copied().map(monday_of).collect();
+    break_weeks.sort_unstable();
+    break_weeks.dedup();
+
+    let first = monday_of(term.start_date);
+    let last = monday_of(term.end_date);
+
+    if let Some(outside) = break_weeks.iter().find(|monday| **monday < first || **monday > last) {
+        return Err(Error::Validation(format!("Break week {} is outside the term", outside)));
+    }
+
+    Ok(break_weeks)
+}
+
+async fn ensure_name_free(
+    pool: &SqlitePool,
+    name: &str,
+    except_id: Option<&str>,
+) -> Result<(), Error> {
+    let existing: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM terms WHERE name = ?"
+    )
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    match existing {
+        Some(id) if Some(id.as_str()) != except_id => {
+            Err(Error::Conflict(format!("Term name already in use: {}", name)))
+        }
+        _ => Ok(()),
+    }
+}
+
+const TERM_COLUMNS: &str = r#"
+    t.id,
+    t.name,
+    t.start_date,
+    t.end_date,
+    (SELECT json_group_array(date)
+     FROM (SELECT date FROM term_breaks WHERE term_id = t.id ORDER BY date)) AS break_weeks
+"#;
+
+fn term_from_row(row: &SqliteRow) -> Result<Term, Error> {
+    Ok(Term {
+        id: row.get("id"),
+        name: row.get("name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        break_weeks: serde_json::from_str(row.get("break_weeks"))?
+    })
+}
+
+async fn write_break_weeks(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    term_id: &str,
+    break_weeks: &[NaiveDate],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM term_breaks WHERE term_id = ?")
+        .bind(term_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for date in break_weeks {
+        sqlx::query("INSERT INTO term_breaks (term_id, date) VALUES (?, ?)")
+            .bind(term_id)
+            .bind(date)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_terms(
+    pool: &SqlitePool,
+) -> Result<Vec<Term>, Error> {
+    let rows = sqlx::query(&format!("SELECT {} FROM terms t ORDER BY t.start_date, t.name", TERM_COLUMNS))
+        .fetch_all(pool)
+        .await?;
+
+    rows.iter().map(term_from_row).collect()
+}
+
+pub async fn get_term(
+    pool: &SqlitePool,
+    term_id: String,
+) -> Result<Term, Error> {
+    let row = sqlx::query(&format!("SELECT {} FROM terms t WHERE t.id = ?", TERM_COLUMNS))
+        .bind(&term_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Term not found: {}", term_id)))?;
+
+    term_from_row(&row)
+}
+
+/// The term a course belongs to, if any.
+pub(crate) async fn get_course_term(
+    pool: &SqlitePool,
+    course_id: &str,
+) -> Result<Option<Term>, Error> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM terms t JOIN courses c ON c.term_id = t.id WHERE c.id = ?",
+        TERM_COLUMNS
+    ))
+    .bind(course_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.as_ref().map(term_from_row).transpose()
+}
+
+pub async fn create_term(
+    pool: &SqlitePool,
+    term: TermDraft,
+) -> Result<Term, Error> {
+    let break_weeks = validate_term(&term)?;
+    ensure_name_free(pool, term.name.trim(), None).await?;
+
+    let term = Term {
+        id: Uuid::new_v4().to_string(),
+        name: term.name.trim().to_string(),
+        start_date: term.start_date,
+        end_date: term.end_date,
+        break_weeks
+    };
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("INSERT INTO terms (id, name, start_date, end_date) VALUES (?, ?, ?, ?)")
+        .bind(&term.id)
+        .bind(&term.name)
+        .bind(term.start_date)
+        .bind(term.end_date)
+        .execute(&mut *tx)
+        .await?;
+
+    write_break_weeks(&mut tx, &term.id, &term.break_weeks).await?;
+
+    tx.commit().await?;
+
+    Ok(term)
+}
+
+/// Changes the name, dates and breaks of a term. Weeks already scheduled
+/// keep their dates until their course is activated again.
+pub async fn update_term(
+    pool: &SqlitePool,
+    term_id: String,
+    term: TermDraft,
+) -> Result<(), Error> {
+    let break_weeks = validate_term(&term)?;
+    ensure_name_free(pool, term.name.trim(), Some(&term_id)).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query("UPDATE terms SET name = ?, start_date = ?, end_date = ? WHERE id = ?")
+        .bind(term.name.trim())
+        .bind(term.start_date)
+        .bind(term.end_date)
+        .bind(&term_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Term not found: {}", term_id)));
+    }
+
+    write_break_weeks(&mut tx, &term_id, &break_weeks).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Deletes a term. Its courses stay, without a term.
+pub async fn delete_term(
+    pool: &SqlitePool,
+    term_id: String,
+) -> Result<(), Error> {
+    sqlx::query("DELETE FROM terms WHERE id = ?")
+        .bind(term_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A term, how far into it this week is, and the progress of each course
+/// in it.
+pub async fn get_term_overview(
+    pool: &SqlitePool,
+    term_id: String,
+) -> Result<TermOverview, Error> {
+    let term = get_term(pool, term_id).await?;
+    let weeks = term.study_weeks();
+
+    let this_monday = monday_of(Local::now().date_naive());
+    let current_week = weeks
+        .iter()
+        .position(|monday| *monday == this_monday)
+        .map(|i| i as i64 + 1);
+
+    let query = CourseQuery {
+        terms: vec![term.id.clone()],
+        ..CourseQuery::default()
+    };
+
+    let courses = get_courses(pool, query).await?.courses;
+
+    Ok(TermOverview {
+        num_weeks: weeks.len() as i64,
+        current_week,
+        courses,
+        term
+    })
+}
@@ -0,0 +1,192 @@
+use chrono::{Duration, Utc};
+use sqlx::{Row, SqlitePool};
+
+use crate::courses::resolve_trashed_course;
+use crate::departments::revive_departments;
+use crate::error::Error;
+use crate::journal::{forget_courses, forget_departments};
+use crate::types::TrashedCourse;
+
+/// Permanently deletes the given trashed courses along with their weeks and
+/// targets, and any department that was only waiting for them. Courses that
+/// are not in the trash are left alone.
+async fn purge_courses(pool: &SqlitePool, course_ids: &[String]) -> Result<(), Error> {
+    let ids = serde_json::to_string(course_ids)?;
+    let mut tx = pool.begin().await?;
+
+    let trashed: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM courses WHERE deleted_at IS NOT NULL AND id IN (SELECT value FROM json_each(?))"
+    )
+    .bind(&ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    forget_courses(&mut tx, &trashed).await?;
+
+    let queries = [
+        "DELETE FROM targets WHERE week_id IN (
+           SELECT w.id FROM weeks w
+           JOIN courses c ON w.course_id = c.id
+           WHERE c.deleted_at IS NOT NULL
+             AND c.id IN (SELECT value FROM json_each(?))
+         )",
+        "DELETE FROM weeks WHERE course_id IN (
+           SELECT id FROM courses
+           WHERE deleted_at IS NOT NULL
+             AND id IN (SELECT value FROM json_each(?))
+         )",
+        "DELETE FROM courses
+         WHERE deleted_at IS NOT NULL
+           AND id IN (SELECT value FROM json_each(?))",
+    ];
+
+    for query in queries {
+        sqlx::query(query)
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // Departments deleted along with their courses go once the last of
+    // those courses has been purged.
+    let departments: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM departments
+         WHERE deleted_at IS NOT NULL
+           AND id NOT IN (SELECT department_id FROM courses)"
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    forget_departments(&mut tx, &departments).await?;
+
+    sqlx::query("DELETE FROM departments WHERE id IN (SELECT value FROM json_each(?))")
+        .bind(serde_json::to_string(&departments)?)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Purges courses that have been in the trash longer than `days`.
+pub async fn purge_expired(pool: &SqlitePool, days: i64) -> Result<(), Error> {
+    let expired: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM courses WHERE deleted_at < ?"
+    )
+    .bind(Utc::now() - Duration::days(days))
+    .fetch_all(pool)
+    .await?;
+
+    purge_courses(pool, &expired).await
+}
+
+pub async fn list_trash(
+    pool: &SqlitePool,
+) -> Result<Vec<TrashedCourse>, Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          c.id          AS course_id,
+          c.serial      AS course_serial,
+          c.name        AS course_name,
+          c.deleted_at  AS deleted_at,
+          d.code        AS dept_code
+        FROM courses c
+        JOIN departments d ON c.department_id = d.id
+        WHERE c.deleted_at IS NOT NULL
+        ORDER BY c.deleted_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let courses = rows
+        .into_iter()
+        .map(|row| TrashedCourse {
+            id: row.get("course_id"),
+            department: row.get("dept_code"),
+            serial: row.get("course_serial"),
+            name: row.get("course_name"),
+            deleted_at: row.get("deleted_at")
+        })
+        .collect();
+
+    Ok(courses)
+}
+
+pub async fn restore_course(
+    pool: &SqlitePool,
+    course_id: String,
+) -> Result<(), Error> {
+    let course_id = resolve_trashed_course(pool, &course_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE courses
+        SET deleted_at = NULL
+        WHERE id = ?
+        "#
+    )
+    .bind(&course_id)
+    .execute(&mut *tx)
+    .await?;
+
+    revive_departments(&mut tx, &[course_id]).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Empties the trash, or removes a single trashed course when `course_id`
+/// is given.
+pub async fn purge_trash(
+    pool: &SqlitePool,
+    course_id: Option<String>,
+) -> Result<(), Error> {
+    let course_ids = match course_id {
+        Some(course_id) => vec![resolve_trashed_course(pool, &course_id).await?],
+        None => sqlx::query_scalar("SELECT id FROM courses WHERE deleted_at IS NOT NULL")
+            .fetch_all(pool)
+            .await?,
+    };
+
+    purge_courses(pool, &course_ids).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::courses::{delete_course, resolve_course, set_course_priority};
+    use crate::db::memory;
+
+    #[tokio::test]
+    async fn trashed_courses_only_resolve_for_the_trash() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        delete_course(&pool, "algo".to_string()).await.unwrap();
+
+        assert!(matches!(resolve_course(&pool, "algo").await, Err(Error::NotFound(_))));
+        assert!(matches!(
+            set_course_priority(&pool, "CS-241".to_string(), 3).await,
+            Err(Error::NotFound(_))
+        ));
+
+        restore_course(&pool, "CS-241".to_string()).await.unwrap();
+        assert_eq!(resolve_course(&pool, "CS-241").await.unwrap(), "algo");
+        assert!(matches!(purge_trash(&pool, Some("algo".to_string())).await, Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,486 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::Type;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DepartmentDraft {
+    pub code: String,
+    pub name: String
+}
+
+#[derive(Debug, Serialize)]
+pub struct Department {
+    pub id: String,
+    pub code: String,
+    pub name: String
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepartmentDeletePolicy {
+    Restrict,
+    Cascade
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TargetDraft {
+    pub serial: i64,
+    pub text: String,
+    pub source: String,
+    pub estimated_minutes: Option<i64>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Target {
+    pub id: String,
+    pub serial: i64,
+    pub text: String,
+    pub source: String,
+    pub is_complete: bool,
+    pub estimated_minutes: Option<i64>,
+    pub seconds_spent: i64
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WeekDraft {
+    pub serial: i64,
+    pub text: String,
+    pub targets: Vec<TargetDraft>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Week {
+    pub id: String,
+    pub serial: i64,
+    pub text: String,
+    pub date: Option<NaiveDate>,
+    pub is_complete: bool,
+    pub estimated_minutes: Option<i64>,
+    pub seconds_spent: i64,
+    pub targets: Vec<Target>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeksPreview {
+    pub num_complete: i64,
+    pub num_total: i64
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "lowercase")]
+pub enum CourseStatus {
+    Draft,
+    Inactive,
+    Active,
+    Complete
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "lowercase")]
+pub enum CourseLevel {
+    Intro,
+    Intermediate,
+    Advanced,
+    Graduate
+}
+
+/// A course named the way people refer to it: its department code and
+/// serial, written `CS-241`, `CS 241` or `CS241`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseCode {
+    pub department: String,
+    pub serial: i64
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CourseDraft {
+    pub department: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub book: Option<String>,
+    pub prompt: Option<String>,
+    pub level: Option<CourseLevel>,
+    pub serial: Option<i64>
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CourseContentDraft {
+    pub name: String,
+    pub description: Option<String>,
+    pub book: Option<String>,
+    pub prompt: Option<String>,
+    pub weeks: Vec<WeekDraft>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Course {
+    pub id: String,
+    pub department: Department,
+    pub serial: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub book: Option<String>,
+    pub prompt: Option<String>,
+    pub level: Option<CourseLevel>,
+    pub status: CourseStatus,
+    pub priority: i64,
+    pub term_id: Option<String>,
+    pub estimated_minutes: Option<i64>,
+    pub seconds_spent: i64,
+    pub weeks: Vec<Week>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetsPreview {
+    pub num_complete: i64,
+    pub num_total: i64
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoursePreview {
+    pub id: String,
+    pub department: String,
+    pub serial: i64,
+    pub name: String,
+    pub level: Option<CourseLevel>,
+    pub status: CourseStatus,
+    pub priority: i64,
+    pub term_id: Option<String>,
+    pub weeks: WeeksPreview,
+    pub targets: TargetsPreview,
+    pub next_date: Option<NaiveDate>,
+    pub num_overdue_weeks: i64,
+    pub last_activity_at: Option<DateTime<Utc>>
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CourseSort {
+    #[default]
+    Code,
+    Priority,
+    Name,
+    Progress,
+    NextDate,
+    LastActivity
+}
+
+/// Which courses `get_courses` returns and in what order. Every filter is
+/// optional; `cursor` is the `next_cursor` of the previous page.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseQuery {
+    #[serde(default)]
+    pub statuses: Vec<CourseStatus>,
+    #[serde(default)]
+    pub departments: Vec<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub terms: Vec<String>,
+    #[serde(default)]
+    pub has_overdue: Option<bool>,
+    #[serde(default)]
+    pub sort: CourseSort,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub cursor: Option<String>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoursePage {
+    pub courses: Vec<CoursePreview>,
+    pub next_cursor: Option<String>
+}
+
+#[derive(Debug, Serialize)]
+pub struct CourseHeader {
+    pub id: String,
+    pub department: String,
+    pub serial: i64,
+    pub name: String,
+    pub status: CourseStatus
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleItem {
+    pub course: CourseHeader,
+    pub weeks: Vec<Week>
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", content = "id", rename_all = "lowercase")]
+pub enum NoteOwner {
+    Course(String),
+    Week(String),
+    Target(String)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    pub owner: NoteOwner,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSearchHit {
+    pub note: Note,
+    pub snippet: String,
+    pub course: CourseHeader,
+    pub week_serial: Option<i64>
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchFilters {
+    #[serde(default)]
+    pub departments: Vec<String>,
+    #[serde(default)]
+    pub statuses: Vec<CourseStatus>,
+    #[serde(default)]
+    pub tags: Vec<String>
+}
+
+#[derive(Debug, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "lowercase")]
+pub enum SearchHitKind {
+    Course,
+    Week,
+    Target
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    pub id: String,
+    pub course: CourseHeader,
+    pub week_serial: Option<i64>,
+    pub highlight: String
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudySession {
+    pub id: String,
+    pub target_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i64>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityIssueKind {
+    DuplicateCourseSerial,
+    DuplicateWeekSerial,
+    WeekSerialGap,
+    NonMondayWeek,
+    DanglingRow
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    pub ids: Vec<String>,
+    pub message: String
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedCourse {
+    pub id: String,
+    pub department: String,
+    pub serial: i64,
+    pub name: String,
+    pub deleted_at: DateTime<Utc>
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ContentOrigin {
+    Manual,
+    Llm,
+    Import,
+    Revert,
+    Original
+}
+
+/// A course as written by `export_courses`: where it sits in the catalog and
+/// its content, without ids or progress.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchivedCourse {
+    pub department: String,
+    pub serial: i64,
+    pub level: Option<CourseLevel>,
+    pub content: CourseContentDraft
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub departments: Vec<DepartmentDraft>,
+    pub courses: Vec<ArchivedCourse>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseVersion {
+    pub id: String,
+    pub origin: ContentOrigin,
+    pub name: String,
+    pub num_weeks: i64,
+    pub created_at: DateTime<Utc>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub before: Option<String>,
+    pub after: Option<String>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetDiff {
+    pub serial: i64,
+    pub kind: ChangeKind,
+    pub before: Option<TargetDraft>,
+    pub after: Option<TargetDraft>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekDiff {
+    pub serial: i64,
+    pub kind: ChangeKind,
+    pub before_text: Option<String>,
+    pub after_text: Option<String>,
+    pub targets: Vec<TargetDiff>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseDiff {
+    pub name: Option<FieldChange>,
+    pub description: Option<FieldChange>,
+    pub book: Option<FieldChange>,
+    pub weeks: Vec<WeekDiff>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagDraft {
+    pub name: String,
+    pub color: String
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub color: String
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", content = "id", rename_all = "lowercase")]
+pub enum TaggedItem {
+    Course(String),
+    Week(String),
+    Target(String)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermDraft {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(default)]
+    pub break_weeks: Vec<NaiveDate>
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Term {
+    pub id: String,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub break_weeks: Vec<NaiveDate>
+}
+
+/// A term with where it stands today and the progress of its courses.
+/// `current_week` counts study weeks only, so it is `None` before and after
+/// the term and during breaks.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermOverview {
+    pub term: Term,
+    pub num_weeks: i64,
+    pub current_week: Option<i64>,
+    pub courses: Vec<CoursePreview>
+}
+
+/// Weeks that activating a course could not fit inside its term. They are
+/// still scheduled, on the Mondays after the term ends.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermOverflow {
+    pub term: String,
+    pub end_date: NaiveDate,
+    pub num_weeks: i64,
+    pub last_date: NaiveDate
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataDirectorySource {
+    Flag,
+    Env,
+    Portable,
+    Moved,
+    Default
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirectory {
+    pub path: PathBuf,
+    pub source: DataDirectorySource
+}
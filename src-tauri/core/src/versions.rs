@@ -0,0 +1,388 @@
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+use crate::courses::{resolve_course, write_content};
+use crate::journal::{record, snapshot_content, Operation};
+use crate::error::Error;
+use crate::types::{ChangeKind, ContentOrigin, CourseContentDraft, CourseDiff, CourseVersion, FieldChange, TargetDiff, TargetDraft, WeekDiff, WeekDraft};
+
+type Tx<'a> = sqlx::Transaction<'a, sqlx::Sqlite>;
+
+/// Stores `draft` as a new version of the course.
+pub(crate) async fn save_version(
+    tx: &mut Tx<'_>,
+    course_id: &str,
+    draft: &CourseContentDraft,
+    origin: ContentOrigin,
+) -> Result<(), Error> {
+    let content = serde_json::to_string(draft)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO course_versions (id, course_id, origin, content, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(course_id)
+    .bind(origin)
+    .bind(content)
+    .bind(Utc::now())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Stores the content a course has now as its first version, unless it
+/// already has versions or no weeks yet. Courses that existed before
+/// versions were kept, or were never edited since, would otherwise lose the
+/// content they started with on their first edit.
+pub(crate) async fn save_original_version(
+    tx: &mut Tx<'_>,
+    course_id: &str,
+) -> Result<(), Error> {
+    let has_versions: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM course_versions WHERE course_id = ?)"
+    )
+    .bind(course_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if has_versions {
+        return Ok(());
+    }
+
+    let course = sqlx::query("SELECT name, description, book, prompt FROM courses WHERE id = ?")
+        .bind(course_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let week_rows = sqlx::query("SELECT id, serial, text FROM weeks WHERE course_id = ? ORDER BY serial")
+        .bind(course_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+    if week_rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut weeks = Vec::with_capacity(week_rows.len());
+
+    for row in week_rows {
+        let targets = sqlx::query(
+            "SELECT serial, text, source, estimated_minutes FROM targets WHERE week_id = ? ORDER BY serial"
+        )
+        .bind(row.get::<String, _>("id"))
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|target| TargetDraft {
+            serial: target.get("serial"),
+            text: target.get("text"),
+            source: target.get("source"),
+            estimated_minutes: target.get("estimated_minutes")
+        })
+        .collect();
+
+        weeks.push(WeekDraft {
+            serial: row.get("serial"),
+            text: row.get("text"),
+            targets
+        });
+    }
+
+    let draft = CourseContentDraft {
+        name: course.get("name"),
+        description: course.get("description"),
+        book: course.get("book"),
+        prompt: course.get("prompt"),
+        weeks
+    };
+
+    save_version(tx, course_id, &draft, ContentOrigin::Original).await
+}
+
+async fn load_version(
+    pool: &SqlitePool,
+    version_id: &str,
+) -> Result<(String, CourseContentDraft), Error> {
+    let row = sqlx::query("SELECT course_id, content FROM course_versions WHERE id = ?")
+        .bind(version_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(Error::NotFound("Version not found".to_string()))?;
+
+    let content = serde_json::from_str(row.get("content"))?;
+
+    Ok((row.get("course_id"), content))
+}
+
+fn field_change(before: &Option<String>, after: &Option<String>) -> Option<FieldChange> {
+    (before != after).then(|| FieldChange {
+        before: before.clone(),
+        after: after.clone()
+    })
+}
+
+pub async fn list_course_versions(
+    pool: &SqlitePool,
+    course_id: String,
+) -> Result<Vec<CourseVersion>, Error> {
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, origin, content, created_at
+        FROM course_versions
+        WHERE course_id = ?
+        ORDER BY created_at DESC, rowid DESC
+        "#
+    )
+    .bind(&course_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut versions = Vec::new();
+
+    for row in rows {
+        let content: CourseContentDraft = serde_json::from_str(row.get("content"))?;
+
+        versions.push(CourseVersion {
+            id: row.get("id"),
+            origin: row.get("origin"),
+            name: content.name,
+            num_weeks: content.weeks.len() as i64,
+            created_at: row.get("created_at")
+        });
+    }
+
+    Ok(versions)
+}
+
+/// Compares two versions of the same course. Weeks are matched by serial and
+/// targets by serial within their week; only differences are returned.
+pub async fn diff_course_versions(
+    pool: &SqlitePool,
+    from_version_id: String,
+    to_version_id: String,
+) -> Result<CourseDiff, Error> {
+    let (from_course, from) = load_version(pool, &from_version_id).await?;
+    let (to_course, to) = load_version(pool, &to_version_id).await?;
+
+    if from_course != to_course {
+        return Err(Error::Validation("Versions belong to different courses".to_string()));
+    }
+
+    let before_weeks: BTreeMap<i64, _> = from.weeks.iter().map(|w| (w.serial, w)).collect();
+    let after_weeks: BTreeMap<i64, _> = to.weeks.iter().map(|w| (w.serial, w)).collect();
+    let week_serials: BTreeSet<i64> = before_weeks.keys().chain(after_weeks.keys()).copied().collect();
+
+    let mut weeks = Vec::new();
+
+    for serial in week_serials {
+        let before = before_weeks.get(&serial);
+        let after = after_weeks.get(&serial);
+
+        let before_targets: BTreeMap<i64, _> = before
+            .map(|w| w.targets.iter().map(|t| (t.serial, t)).collect())
+            .unwrap_or_default();
+        let after_targets: BTreeMap<i64, _> = after
+            .map(|w| w.targets.iter().map(|t| (t.serial, t)).collect())
+            .unwrap_or_default();
+        let target_serials: BTreeSet<i64> = before_targets.keys().chain(after_targets.keys()).copied().collect();
+
+        let mut targets = Vec::new();
+
+        for target_serial in target_serials {
+            let before = before_targets.get(&target_serial).copied();
+            let after = after_targets.get(&target_serial).copied();
+
+            let kind = match (before, after) {
+                (Some(b), Some(a)) if b == a => continue,
+                (Some(_), Some(_)) => ChangeKind::Changed,
+                (Some(_), None) => ChangeKind::Removed,
+                _ => ChangeKind::Added
+            };
+
+            targets.push(TargetDiff {
+                serial: target_serial,
+                kind,
+                before: before.cloned(),
+                after: after.cloned()
+            });
+        }
+
+        let kind = match (before, after) {
+            (Some(b), Some(a)) if b.text == a.text && targets.is_empty() => continue,
+            (Some(_), Some(_)) => ChangeKind::Changed,
+            (Some(_), None) => ChangeKind::Removed,
+            _ => ChangeKind::Added
+        };
+
+        weeks.push(WeekDiff {
+            serial,
+            kind,
+            before_text: before.map(|w| w.text.clone()),
+            after_text: after.map(|w| w.text.clone()),
+            targets
+        });
+    }
+
+    Ok(CourseDiff {
+        name: field_change(&Some(from.name), &Some(to.name)),
+        description: field_change(&from.description, &to.description),
+        book: field_change(&from.book, &to.book),
+        weeks
+    })
+}
+
+/// Restores the content of a course to an earlier version. Weeks and targets
+/// that survive unchanged keep their dates and completion. Courses in the
+/// trash cannot be reverted.
+pub async fn revert_course_version(
+    pool: &SqlitePool,
+    version_id: String,
+) -> Result<(), Error> {
+    let (course_id, draft) = load_version(pool, &version_id).await?;
+    let course_id = resolve_course(pool, &course_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let before = snapshot_content(&mut tx, &course_id).await?;
+
+    write_content(&mut tx, &course_id, &draft, true).await?;
+
+    save_version(&mut tx, &course_id, &draft, ContentOrigin::Revert).await?;
+
+    let after = snapshot_content(&mut tx, &course_id).await?;
+
+    record(
+        &mut tx,
+        "Revert course",
+        Operation::Content { course_id: course_id.clone(), content: before },
+        Operation::Content { course_id, content: after },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::courses::{delete_course, get_course, update_course};
+    use crate::db::memory;
+
+    fn content(week: &str, target: &str) -> CourseContentDraft {
+        CourseContentDraft {
+            name: "Algorithms".to_string(),
+            description: None,
+            book: None,
+            prompt: None,
+            weeks: vec![WeekDraft {
+                serial: 1,
+                text: week.to_string(),
+                targets: vec![TargetDraft {
+                    serial: 1,
+                    text: target.to_string(),
+                    source: "Book".to_string(),
+                    estimated_minutes: None
+                }]
+            }]
+        }
+    }
+
+    #[tokio::test]
+    async fn the_content_before_the_first_version_can_be_reverted_to() {
+        let pool = memory().await.unwrap();
+
+        // Courses from before versions were kept have none.
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            INSERT INTO weeks (id, course_id, serial, text) VALUES ('sorting', 'algo', 1, 'Sorting');
+            INSERT INTO targets (id, week_id, serial, text, source) VALUES ('read', 'sorting', 1, 'Read', 'Book');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        update_course(&pool, "algo".to_string(), content("Nonsense", "Garbage"), None)
+            .await
+            .unwrap();
+
+        let versions = list_course_versions(&pool, "algo".to_string()).await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(matches!(versions[1].origin, ContentOrigin::Original));
+
+        revert_course_version(&pool, versions[1].id.clone()).await.unwrap();
+
+        let course = get_course(&pool, "algo".to_string()).await.unwrap();
+        assert_eq!(course.weeks[0].text, "Sorting");
+        assert_eq!(course.weeks[0].targets[0].text, "Read");
+    }
+
+    #[tokio::test]
+    async fn versions_made_at_once_are_listed_newest_first() {
+        let pool = memory().await.unwrap();
+
+        let created_at = Utc::now();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for (id, origin) in [("first", ContentOrigin::Original), ("second", ContentOrigin::Manual)] {
+            sqlx::query(
+                "INSERT INTO course_versions (id, course_id, origin, content, created_at) VALUES (?, 'algo', ?, ?, ?)"
+            )
+            .bind(id)
+            .bind(origin)
+            .bind(serde_json::to_string(&content("Sorting", "Read")).unwrap())
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let versions = list_course_versions(&pool, "algo".to_string()).await.unwrap();
+        let ids: Vec<&str> = versions.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, ["second", "first"]);
+    }
+
+    #[tokio::test]
+    async fn trashed_courses_cannot_be_reverted() {
+        let pool = memory().await.unwrap();
+
+        sqlx::raw_sql(
+            r#"
+            INSERT INTO departments (id, code, name) VALUES ('cs', 'CS', 'Computer Science');
+            INSERT INTO courses (id, department_id, serial, name) VALUES ('algo', 'cs', 241, 'Algorithms');
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        update_course(&pool, "algo".to_string(), content("Sorting", "Read"), None)
+            .await
+            .unwrap();
+        let versions = list_course_versions(&pool, "algo".to_string()).await.unwrap();
+        delete_course(&pool, "algo".to_string()).await.unwrap();
+
+        let reverted = revert_course_version(&pool, versions[0].id.clone()).await;
+        assert!(matches!(reverted, Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,65 @@
+use sqlx::SqlitePool;
+
+use crate::journal::{record, Operation};
+use crate::error::Error;
+
+/// Fails with `NotFound` unless `week_id` is a week of a course that is not
+/// in the trash.
+pub async fn ensure_week(
+    pool: &SqlitePool,
+    week_id: &str,
+) -> Result<(), Error> {
+    let exists: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT 1
+        FROM weeks w
+        JOIN courses c ON w.course_id = c.id
+        WHERE w.id = ? AND c.deleted_at IS NULL
+        "#
+    )
+    .bind(week_id)
+    .fetch_optional(pool)
+    .await?;
+
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound(format!("Week not found: {}", week_id))),
+    }
+}
+
+pub async fn change_week_status(
+    pool: &SqlitePool,
+    week_id: String,
+    status: bool,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous: bool = sqlx::query_scalar("SELECT is_complete FROM weeks WHERE id = ?")
+        .bind(&week_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE weeks
+        SET is_complete = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(status)
+    .bind(&week_id)
+    .execute(&mut *tx)
+    .await?;
+
+    record(
+        &mut tx,
+        "Change week status",
+        Operation::WeekStatus { week_id: week_id.clone(), status: previous },
+        Operation::WeekStatus { week_id, status },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
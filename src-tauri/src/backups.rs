@@ -0,0 +1,213 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::settings::{load_settings, settings_path, BackupRetention};
+use crate::db::DatabaseState;
+use crate::error::{report, BackgroundTask, Error};
+use crate::profiles::profile_dir;
+use crate::types::{Backup, BackupKind};
+
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(60 * 60);
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+impl BackupKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            BackupKind::Auto => "auto",
+            BackupKind::Manual => "manual",
+            BackupKind::PreRestore => "pre-restore"
+        }
+    }
+}
+
+pub(crate) fn backups_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    let dir = profile_dir(app)?.join("backups");
+
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+/// Recovers the kind and creation time from a backup file name such as
+/// `auto-20240902T063000.000Z.db`. Other files in the directory are ignored.
+fn parse_name(name: &str) -> Option<(BackupKind, DateTime<Utc>)> {
+    let stem = name.strip_suffix(".db")?;
+
+    [BackupKind::Auto, BackupKind::Manual, BackupKind::PreRestore]
+        .into_iter()
+        .find_map(|kind| {
+            let timestamp = stem.strip_prefix(kind.prefix())?.strip_prefix('-')?;
+            let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+            Some((kind, created_at.and_utc()))
+        })
+}
+
+/// All backups on disk, newest first.
+pub(crate) fn list(app: &AppHandle) -> Result<Vec<Backup>, Error> {
+    let mut backups = Vec::new();
+
+    for entry in fs::read_dir(backups_dir(app)?)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if let Some((kind, created_at)) = parse_name(&name) {
+            backups.push(Backup {
+                name,
+                kind,
+                created_at,
+                size_bytes: entry.metadata()?.len()
+            });
+        }
+    }
+
+    backups.sort_by_key(|b| Reverse(b.created_at));
+
+    Ok(backups)
+}
+
+/// Writes a consistent copy of the live database with `VACUUM INTO`, which
+/// is safe to run while other connections are reading and writing.
+pub(crate) async fn create(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    kind: BackupKind,
+) -> Result<Backup, Error> {
+    let created_at = Utc::now();
+    let name = format!("{}-{}.db", kind.prefix(), created_at.format(TIMESTAMP_FORMAT));
+    let path = backups_dir(app)?.join(&name);
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy())
+        .execute(pool)
+        .await?;
+
+    Ok(Backup {
+        name,
+        kind,
+        created_at,
+        size_bytes: fs::metadata(&path)?.len()
+    })
+}
+
+/// Names of the automatic backups the retention policy keeps. `backups` must
+/// be sorted newest first, so the first backup seen in each period wins.
+fn retained(backups: &[Backup], retention: &BackupRetention) -> HashSet<String> {
+    let periods = [
+        (retention.daily, "%Y-%m-%d"),
+        (retention.weekly, "%G-W%V"),
+        (retention.monthly, "%Y-%m"),
+    ];
+
+    let mut keep = HashSet::new();
+
+    for (count, format) in periods {
+        let mut seen = HashSet::new();
+
+        for backup in backups.iter().filter(|b| b.kind == BackupKind::Auto) {
+            if seen.len() >= count {
+                break;
+            }
+
+            if seen.insert(backup.created_at.format(format).to_string()) {
+                keep.insert(backup.name.clone());
+            }
+        }
+    }
+
+    keep
+}
+
+/// Deletes automatic backups that fall outside the retention policy. Manual
+/// and pre-restore backups are only ever removed by the user.
+pub(crate) fn prune(app: &AppHandle) -> Result<(), Error> {
+    let retention = load_settings(&settings_path(app)?)?.backup_retention;
+    let backups = list(app)?;
+    let keep = retained(&backups, &retention);
+    let dir = backups_dir(app)?;
+
+    for backup in backups {
+        if backup.kind == BackupKind::Auto && !keep.contains(&backup.name) {
+            fs::remove_file(dir.join(&backup.name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes an automatic backup and prunes old ones.
+pub async fn run(app: &AppHandle, pool: &SqlitePool) -> Result<(), Error> {
+    create(app, pool, BackupKind::Auto).await?;
+    prune(app)
+}
+
+/// Starts the background task that takes an automatic backup whenever the
+/// newest one is more than a day old.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let latest = list(&app).map(|backups| {
+                backups
+                    .into_iter()
+                    .find(|b| b.kind == BackupKind::Auto)
+                    .map(|b| b.created_at)
+            });
+
+            let due = match latest {
+                Ok(Some(created_at)) => Utc::now() - created_at >= Duration::days(1),
+                Ok(None) => true,
+                Err(_) => false
+            };
+
+            let state = app.try_state::<DatabaseState>();
+
+            if let (true, Some(state)) = (due, state) {
+                if let Err(e) = run(&app, &state.pool()).await {
+                    report(&app, BackgroundTask::Backup, &e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn backup(name: &str, kind: BackupKind, (month, day, hour): (u32, u32, u32)) -> Backup {
+        Backup {
+            name: name.to_string(),
+            kind,
+            created_at: Utc.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap(),
+            size_bytes: 0
+        }
+    }
+
+    #[test]
+    fn retention_keeps_the_newest_backup_of_each_period() {
+        let backups = [
+            backup("manual", BackupKind::Manual, (3, 3, 20)),
+            backup("mar-3-late", BackupKind::Auto, (3, 3, 18)),
+            backup("mar-3-early", BackupKind::Auto, (3, 3, 8)),
+            backup("mar-2", BackupKind::Auto, (3, 2, 8)),
+            backup("mar-1", BackupKind::Auto, (3, 1, 8)),
+            backup("feb-10", BackupKind::Auto, (2, 10, 8)),
+            backup("jan-5", BackupKind::Auto, (1, 5, 8)),
+        ];
+        let retention = BackupRetention { daily: 2, weekly: 2, monthly: 2 };
+
+        let mut kept: Vec<String> = retained(&backups, &retention).into_iter().collect();
+        kept.sort();
+
+        // mar-1 is a Sunday, so it ends the week before mar-2.
+        assert_eq!(kept, ["feb-10", "mar-1", "mar-2", "mar-3-late"]);
+    }
+}
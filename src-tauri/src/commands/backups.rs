@@ -0,0 +1,129 @@
+use mnemona_core::db::{connect, is_encrypted, quote, remove_sidecars};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::backups;
+use crate::db::{database_path, DatabaseState};
+use crate::error::Error;
+use crate::types::{Backup, BackupKind};
+
+/// Makes sure `path` is an intact SQLite database before it replaces the
+/// live one.
+async fn verify(path: &Path, passphrase: Option<&str>) -> Result<(), Error> {
+    let mut options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true);
+
+    if let Some(passphrase) = passphrase {
+        options = options.pragma("key", quote(passphrase));
+    }
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let result: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await?;
+
+    conn.close().await?;
+
+    if result != "ok" {
+        return Err(Error::Validation(format!("Backup is corrupt: {}", result)));
+    }
+
+    Ok(())
+}
+
+/// Puts a copy of `source` in place of the database file and opens a fresh
+/// pool on it. The previous pool must already be closed. The copy is written
+/// and synced next to the database first and then renamed over it, so a
+/// crash part way leaves either the old file or the new one.
+async fn install(
+    state: &DatabaseState,
+    db_path: &Path,
+    source: &Path,
+    passphrase: Option<String>,
+) -> Result<(), Error> {
+    let mut staging = db_path.as_os_str().to_owned();
+    staging.push(".restoring");
+    let staging = PathBuf::from(staging);
+
+    let staged = fs::copy(source, &staging).and_then(|_| File::open(&staging)?.sync_all());
+
+    if let Err(e) = staged {
+        let _ = fs::remove_file(&staging);
+        return Err(e.into());
+    }
+
+    // The old WAL must not be replayed onto the new file.
+    remove_sidecars(db_path);
+
+    fs::rename(&staging, db_path)?;
+
+    let pool = connect(db_path, passphrase.as_deref()).await?;
+    state.replace(pool, passphrase);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_backups(
+    app: AppHandle,
+) -> Result<Vec<Backup>, Error> {
+    backups::list(&app)
+}
+
+#[tauri::command]
+pub async fn create_backup(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+) -> Result<Backup, Error> {
+    let pool: &SqlitePool = &state.pool();
+
+    backups::create(&app, pool, BackupKind::Manual).await
+}
+
+/// Replaces the live database with a backup. The current data is saved as a
+/// pre-restore backup first, and put back if the restored file fails to
+/// open. Commands that run while the swap is in progress fail rather than
+/// touching either file. An encrypted backup is opened with `passphrase`,
+/// or with the current passphrase when none is given.
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+    name: String,
+    passphrase: Option<String>,
+) -> Result<(), Error> {
+    let backup = backups::list(&app)?
+        .into_iter()
+        .find(|b| b.name == name)
+        .ok_or(Error::NotFound("Backup not found".to_string()))?;
+
+    let dir = backups::backups_dir(&app)?;
+    let source = dir.join(&backup.name);
+
+    let current_passphrase = state.passphrase();
+    let backup_passphrase = if is_encrypted(&source) {
+        passphrase.or(current_passphrase.clone())
+    } else {
+        None
+    };
+
+    verify(&source, backup_passphrase.as_deref()).await?;
+
+    let db_path = database_path(&app)?;
+    let pool = state.pool();
+    let safety = backups::create(&app, &pool, BackupKind::PreRestore).await?;
+
+    pool.close().await;
+
+    if let Err(e) = install(&state, &db_path, &source, backup_passphrase).await {
+        install(&state, &db_path, &dir.join(&safety.name), current_passphrase).await?;
+        return Err(e);
+    }
+
+    Ok(())
+}
@@ -1,334 +1,42 @@
-use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
-use sqlx::{SqlitePool, Row};
-use std::collections::HashMap;
-use tauri::State;
-use uuid::Uuid;
+use mnemona_core::courses;
+use tauri::{AppHandle, State};
 
+use crate::commands::settings::{load_settings, settings_path};
 use crate::db::DatabaseState;
-use crate::types::{Course, CourseContentDraft, CourseDraft, CoursePreview, Department, DepartmentDraft, Target, Week, WeeksPreview};
-
-async fn generate_course_serial(
-    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
-    department_id: &str,
-) -> Result<i64, sqlx::Error> {
-    let mut digits = 3;
-
-    loop {
-        let lower = 10_i64.pow(digits - 1);
-        let upper = 10_i64.pow(digits) - 1;
-        let range_size = upper - lower + 1;
-
-        let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM courses
-             WHERE department_id = ? AND serial BETWEEN ? AND ?"
-        )
-        .bind(department_id)
-        .bind(lower)
-        .bind(upper)
-        .fetch_one(&mut **tx)
-        .await?;
-
-        if count < range_size {
-            for _ in 0..10 {
-                let candidate =
-                    lower + (rand::random::<u64>() % range_size as u64) as i64;
-
-                let exists: Option<i64> = sqlx::query_scalar(
-                    "SELECT 1 FROM courses
-                     WHERE department_id = ? AND serial = ?"
-                )
-                .bind(department_id)
-                .bind(candidate)
-                .fetch_optional(&mut **tx)
-                .await?;
-
-                if exists.is_none() {
-                    return Ok(candidate);
-                }
-            }
-        }
-
-        digits += 1;
-    }
-}
+use crate::error::Error;
+use crate::types::{ContentOrigin, Course, CourseContentDraft, CourseDraft, CourseHeader, CoursePage, CourseQuery, DepartmentDraft, TermOverflow};
 
 #[tauri::command]
 pub async fn create_courses(
     state: State<'_, DatabaseState>,
     courses: Vec<CourseDraft>,
     departments: Vec<DepartmentDraft>,
-) -> Result<(), String> {
-    let pool: &SqlitePool = &state.0;
-
-    let mut tx = pool
-        .begin()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    for dept in departments {
-        let existing = sqlx::query(
-            "SELECT id FROM departments WHERE code = ?"
-        )
-        .bind(&dept.code)
-        .fetch_optional(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        match existing {
-            Some(row) => {
-                let id: String = row.get("id");
-
-                sqlx::query(
-                    "UPDATE departments SET name = ? WHERE id = ?"
-                )
-                .bind(&dept.name)
-                .bind(id)
-                .execute(&mut *tx)
-                .await
-                .map_err(|e| e.to_string())?;
-            }
-            None => {
-                let id = Uuid::new_v4().to_string();
-
-                sqlx::query(
-                    "INSERT INTO departments (id, code, name)
-                     VALUES (?, ?, ?)"
-                )
-                .bind(id)
-                .bind(&dept.code)
-                .bind(&dept.name)
-                .execute(&mut *tx)
-                .await
-                .map_err(|e| e.to_string())?;
-            }
-        }
-    }
-
-    let rows = sqlx::query(
-        "SELECT id, code FROM departments"
-    )
-    .fetch_all(&mut *tx)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let mut dept_map: HashMap<String, String> = HashMap::new();
-
-    for row in rows {
-        let id: String = row.get("id");
-        let code: String = row.get("code");
-        dept_map.insert(code, id);
-    }
-
-    for course in courses {
-        let department_id = dept_map
-            .get(&course.department)
-            .ok_or_else(|| format!("Unknown department code: {}", course.department))?
-            .clone();
-
-        let serial = generate_course_serial(&mut tx, &department_id)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let id = Uuid::new_v4().to_string();
-
-        sqlx::query(
-            "INSERT INTO courses
-            (id, department_id, serial, name, description, book, prompt)
-            VALUES (?, ?, ?, ?, ?, ?, ?)"
-        )
-        .bind(id)
-        .bind(department_id)
-        .bind(serial)
-        .bind(course.name)
-        .bind(course.description)
-        .bind(course.book)
-        .bind(course.prompt)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-    }
-
-    tx.commit()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
+) -> Result<(), Error> {
+    courses::create_courses(&state.pool(), courses, departments).await
 }
 
 #[tauri::command]
 pub async fn get_course(
     state: State<'_, DatabaseState>,
     course_id: String,
-) -> Result<Course, String> {
-    let pool: &SqlitePool = &state.0;
-
-    let row = sqlx::query(
-        r#"
-        SELECT
-          c.id            AS course_id,
-          c.serial        AS course_serial,
-          c.name          AS course_name,
-          c.description   AS course_description,
-          c.book          AS course_book,
-          c.prompt        AS course_prompt,
-          c.status        AS course_status,
-          d.id            AS dept_id,
-          d.code          AS dept_code,
-          d.name          AS dept_name
-        FROM courses c
-        JOIN departments d ON c.department_id = d.id
-        WHERE c.id = ?
-        "#
-    )
-    .bind(&course_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let mut course = Course {
-        id: row.get("course_id"),
-        department: Department {
-            id: row.get("dept_id"),
-            code: row.get("dept_code"),
-            name: row.get("dept_name")
-        },
-        serial: row.get::<i64, _>("course_serial"),
-        name: row.get("course_name"),
-        description: row.get("course_description"),
-        book: row.get("course_book"),
-        prompt: row.get("course_prompt"),
-        status: row.get("course_status"),
-        weeks: Vec::new()
-    };
-
-    let week_rows = sqlx::query(
-        r#"
-        SELECT id, serial, text, date, is_complete
-        FROM weeks
-        WHERE course_id = ?
-        ORDER BY serial
-        "#
-    )
-    .bind(&course_id)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let mut week_map: HashMap<String, Week> = HashMap::new();
-    let mut week_ids = Vec::new();
-
-    for row in week_rows {
-        let id: String = row.get("id");
-        week_ids.push(id.clone());
-
-        week_map.insert(
-            id.clone(),
-            Week {
-                id,
-                serial: row.get::<i64, _>("serial"),
-                text: row.get("text"),
-                date: row.get::<Option<NaiveDate>, _>("date"),
-                is_complete: row.get("is_complete"),
-                targets: Vec::new()
-            }
-        );
-    }
-
-    if !week_ids.is_empty() {
-        let placeholders = week_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-
-        let query = format!(
-            r#"
-            SELECT id, week_id, serial, text, source, is_complete
-            FROM targets
-            WHERE week_id IN ({})
-            ORDER BY serial
-            "#,
-            placeholders
-        );
-
-        let mut q = sqlx::query(&query);
-        for id in &week_ids {
-            q = q.bind(id);
-        }
-
-        let target_rows = q
-            .fetch_all(pool)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        for row in target_rows {
-            let week_id: String = row.get("week_id");
-
-            if let Some(week) = week_map.get_mut(&week_id) {
-                week.targets.push(Target {
-                    id: row.get("id"),
-                    serial: row.get::<i64, _>("serial"),
-                    text: row.get("text"),
-                    source: row.get("source"),
-                    is_complete: row.get("is_complete")
-                });
-            }
-        }
-    }
-
-    let mut weeks: Vec<Week> = week_map.into_values().collect();
-    weeks.sort_by_key(|w| w.serial);
-
-    course.weeks = weeks;
+) -> Result<Course, Error> {
+    courses::get_course(&state.pool(), course_id).await
+}
 
-    Ok(course)
+#[tauri::command]
+pub async fn get_course_by_code(
+    state: State<'_, DatabaseState>,
+    code: String,
+) -> Result<Course, Error> {
+    courses::get_course_by_code(&state.pool(), code).await
 }
 
 #[tauri::command]
 pub async fn get_courses(
     state: State<'_, DatabaseState>,
-) -> Result<Vec<CoursePreview>, String> {
-    let pool: &SqlitePool = &state.0;
-
-    let rows = sqlx::query(
-        r#"
-        SELECT
-            c.id     AS course_id,
-            c.serial AS course_serial,
-            c.name   AS course_name,
-            c.status AS course_status,
-            d.code   AS dept_code,
-
-            COUNT(w.id) AS weeks_total,
-            COALESCE(
-                SUM(CASE WHEN w.is_complete = TRUE THEN 1 ELSE 0 END),
-                0
-            ) AS weeks_complete
-        FROM courses c
-        JOIN departments d
-            ON c.department_id = d.id
-        LEFT JOIN weeks w
-            ON w.course_id = c.id
-        GROUP BY c.id
-        ORDER BY d.code, c.serial
-        "#
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let courses = rows
-        .into_iter()
-        .map(|row| CoursePreview {
-            id: row.get("course_id"),
-            department: row.get("dept_code"),
-            serial: row.get::<i64, _>("course_serial"),
-            name: row.get("course_name"),
-            status: row.get("course_status"),
-            weeks: WeeksPreview {
-                num_complete: row.get::<i64, _>("weeks_complete"),
-                num_total: row.get::<i64, _>("weeks_total"),
-            },
-        })
-        .collect();
-
-    Ok(courses)
+    query: Option<CourseQuery>,
+) -> Result<CoursePage, Error> {
+    courses::get_courses(&state.pool(), query.unwrap_or_default()).await
 }
 
 #[tauri::command]
@@ -336,207 +44,62 @@ pub async fn update_course(
     state: State<'_, DatabaseState>,
     course_id: String,
     draft: CourseContentDraft,
-) -> Result<(), String> {
-    let pool: &SqlitePool = &state.0;
-    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        r#"
-        UPDATE courses
-        SET name = ?, description = ?, book = ?
-        WHERE id = ?
-        "#
-    )
-    .bind(&draft.name)
-    .bind(&draft.description)
-    .bind(&draft.book)
-    .bind(&course_id)
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let existing_week_ids: Vec<String> = sqlx::query_scalar(
-        r#"
-        SELECT id FROM weeks WHERE course_id = ?
-        "#
-    )
-    .bind(&course_id)
-    .fetch_all(&mut *tx)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    if !existing_week_ids.is_empty() {
-        let placeholders = existing_week_ids
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(",");
-
-        let delete_targets = format!(
-            "DELETE FROM targets WHERE week_id IN ({})",
-            placeholders
-        );
-
-        let mut q = sqlx::query(&delete_targets);
-        for id in &existing_week_ids {
-            q = q.bind(id);
-        }
-
-        q.execute(&mut *tx).await.map_err(|e| e.to_string())?;
-    }
-
-    sqlx::query("DELETE FROM weeks WHERE course_id = ?")
-        .bind(&course_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    for week in draft.weeks {
-        let week_id = uuid::Uuid::new_v4().to_string();
-
-        sqlx::query(
-            r#"
-            INSERT INTO weeks (id, course_id, serial, text)
-            VALUES (?, ?, ?, ?)
-            "#
-        )
-        .bind(&week_id)
-        .bind(&course_id)
-        .bind(week.serial)
-        .bind(&week.text)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        for target in week.targets {
-            sqlx::query(
-                r#"
-                INSERT INTO targets (id, week_id, serial, text, source)
-                VALUES (?, ?, ?, ?, ?)
-                "#
-            )
-            .bind(uuid::Uuid::new_v4().to_string())
-            .bind(&week_id)
-            .bind(target.serial)
-            .bind(&target.text)
-            .bind(&target.source)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| e.to_string())?;
-        }
-    }
-
-    tx.commit().await.map_err(|e| e.to_string())?;
-    Ok(())
+    origin: Option<ContentOrigin>,
+) -> Result<(), Error> {
+    courses::update_course(&state.pool(), course_id, draft, origin).await
 }
 
 #[tauri::command]
 pub async fn update_course_status(
+    app: AppHandle,
     state: State<'_, DatabaseState>,
     course_id: String,
     status: String,
-) -> Result<(), String> {
-    let pool: &SqlitePool = &state.0;
-
-    match status.as_str() {
-        "draft" | "inactive" | "complete" => {
-            sqlx::query(
-                r#"
-                UPDATE courses
-                SET status = ?
-                WHERE id = ?
-                "#,
-            )
-            .bind(&status)
-            .bind(&course_id)
-            .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
-
-            Ok(())
-        }
-
-        "active" => {
-            sqlx::query(
-                r#"
-                UPDATE courses
-                SET status = ?
-                WHERE id = ?
-                "#,
-            )
-            .bind(&status)
-            .bind(&course_id)
-            .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
-
-            let weeks: Vec<String> = sqlx::query_scalar(
-                r#"
-                SELECT id
-                FROM weeks
-                WHERE course_id = ?
-                AND is_complete = false
-                ORDER BY serial ASC
-                "#,
-            )
-            .bind(&course_id)
-            .fetch_all(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+) -> Result<Option<TermOverflow>, Error> {
+    let capacity = load_settings(&settings_path(&app)?)?.weekly_capacity_minutes;
 
-            let mut date = Local::now().date_naive();
-            while date.weekday() != Weekday::Mon {
-                date -= Duration::days(1);
-            }
-
-            for (i, week_id) in weeks.iter().enumerate() {
-                let week_date = date + Duration::weeks(i as i64);
+    courses::update_course_status(&state.pool(), course_id, status, capacity).await
+}
 
-                sqlx::query(
-                    r#"
-                    UPDATE weeks
-                    SET date = ?
-                    WHERE id = ?
-                    "#,
-                )
-                .bind(week_date)
-                .bind(week_id)
-                .execute(pool)
-                .await
-                .map_err(|e| e.to_string())?;
-            }
+#[tauri::command]
+pub async fn set_course_priority(
+    state: State<'_, DatabaseState>,
+    course_id: String,
+    priority: i64,
+) -> Result<(), Error> {
+    courses::set_course_priority(&state.pool(), course_id, priority).await
+}
 
-            Ok(())
-        }
+#[tauri::command]
+pub async fn set_course_term(
+    state: State<'_, DatabaseState>,
+    course_id: String,
+    term_id: Option<String>,
+) -> Result<(), Error> {
+    courses::set_course_term(&state.pool(), course_id, term_id).await
+}
 
-        _ => Err("invalid status".to_string()),
-    }
+#[tauri::command]
+pub async fn get_focus_course(
+    state: State<'_, DatabaseState>,
+) -> Result<Option<CourseHeader>, Error> {
+    courses::get_focus_course(&state.pool()).await
 }
 
 #[tauri::command]
 pub async fn delete_course(
     state: State<'_, DatabaseState>,
     course_id: String,
-) -> Result<(), String> {
-    let pool: &SqlitePool = &state.0;
-
-    sqlx::query("DELETE FROM targets WHERE week_id IN (SELECT id FROM weeks WHERE course_id = ?)")
-    .bind(&course_id)
-    .execute(pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query("DELETE FROM weeks WHERE course_id = ?")
-    .bind(&course_id)
-    .execute(pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query("DELETE FROM courses WHERE id = ?")
-    .bind(&course_id)
-    .execute(pool)
-    .await
-    .map_err(|e| e.to_string())?;
+) -> Result<(), Error> {
+    courses::delete_course(&state.pool(), course_id).await
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn move_course(
+    state: State<'_, DatabaseState>,
+    course_id: String,
+    department_code: String,
+    serial: Option<i64>,
+) -> Result<i64, Error> {
+    courses::move_course(&state.pool(), course_id, department_code, serial).await
 }
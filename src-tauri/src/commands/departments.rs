@@ -1,27 +1,56 @@
-use sqlx::{Row, SqlitePool};
+use mnemona_core::departments;
 use tauri::State;
 
 use crate::db::DatabaseState;
-use crate::types::DepartmentDraft;
+use crate::error::Error;
+use crate::types::{Department, DepartmentDeletePolicy, DepartmentDraft};
 
 #[tauri::command]
 pub async fn get_departments(
     state: State<'_, DatabaseState>,
-) -> Result<Vec<DepartmentDraft>, String> {
-    let pool: &SqlitePool = &state.0;
+) -> Result<Vec<Department>, Error> {
+    departments::get_departments(&state.pool()).await
+}
 
-    let rows = sqlx::query("SELECT code, name FROM departments ORDER BY code")
-        .fetch_all(pool)
-        .await
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn create_department(
+    state: State<'_, DatabaseState>,
+    department: DepartmentDraft,
+) -> Result<Department, Error> {
+    departments::create_department(&state.pool(), department).await
+}
 
-    let departments = rows
-        .into_iter()
-        .map(|row| DepartmentDraft {
-            code: row.get::<String, _>("code"),
-            name: row.get::<String, _>("name")
-        })
-        .collect();
+#[tauri::command]
+pub async fn rename_department(
+    state: State<'_, DatabaseState>,
+    department_id: String,
+    department: DepartmentDraft,
+) -> Result<(), Error> {
+    departments::rename_department(&state.pool(), department_id, department).await
+}
 
-    Ok(departments)
+#[tauri::command]
+pub async fn delete_department(
+    state: State<'_, DatabaseState>,
+    department_id: String,
+    policy: DepartmentDeletePolicy,
+) -> Result<(), Error> {
+    departments::delete_department(&state.pool(), department_id, policy).await
+}
+
+#[tauri::command]
+pub async fn merge_departments(
+    state: State<'_, DatabaseState>,
+    source_id: String,
+    target_id: String,
+) -> Result<(), Error> {
+    departments::merge_departments(&state.pool(), source_id, target_id).await
+}
+
+#[tauri::command]
+pub async fn renumber_department(
+    state: State<'_, DatabaseState>,
+    department_id: String,
+) -> Result<(), Error> {
+    departments::renumber_department(&state.pool(), department_id).await
 }
@@ -0,0 +1,160 @@
+use mnemona_core::db::{connect, quote, remove_sidecars, unlock_error};
+use sqlx::SqlitePool;
+use std::fs;
+use tauri::{AppHandle, Manager, State};
+
+use crate::backups;
+use crate::commands::trash::purge_expired;
+use crate::db::{database_path, DatabaseState};
+use crate::error::{report, BackgroundTask, Error};
+
+/// Finishes opening the library once its database is available: takes the
+/// startup backup, empties expired trash and hands the pool to commands.
+pub(crate) async fn open_library(app: &AppHandle, pool: SqlitePool, passphrase: Option<String>) {
+    if let Err(e) = backups::run(app, &pool).await {
+        report(app, BackgroundTask::Backup, &e);
+    }
+
+    if let Err(e) = purge_expired(app, &pool).await {
+        report(app, BackgroundTask::PurgeTrash, &e);
+    }
+
+    app.manage(DatabaseState::new(pool, passphrase));
+}
+
+/// Rewrites the open database with `passphrase`, or without encryption when
+/// it is `None`, using `sqlcipher_export` into a staging file that then
+/// replaces the original. The database stays usable if the export fails.
+async fn reencrypt(
+    app: &AppHandle,
+    state: &DatabaseState,
+    passphrase: Option<String>,
+) -> Result<(), Error> {
+    let path = database_path(app)?;
+    let staging = path.with_extension("db.rekey");
+    let _ = fs::remove_file(&staging);
+
+    let pool = state.pool();
+
+    let exported = async {
+        let mut conn = pool.acquire().await?;
+
+        sqlx::query(&format!(
+            "ATTACH DATABASE {} AS target KEY {}",
+            quote(&staging.to_string_lossy()),
+            quote(passphrase.as_deref().unwrap_or(""))
+        ))
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query("SELECT sqlcipher_export('target')")
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query("DETACH DATABASE target")
+            .execute(&mut *conn)
+            .await?;
+
+        Ok::<_, sqlx::Error>(())
+    }
+    .await;
+
+    if let Err(e) = exported {
+        let _ = fs::remove_file(&staging);
+        return Err(e.into());
+    }
+
+    pool.close().await;
+
+    let current = state.passphrase();
+    let installed = fs::rename(&staging, &path).map_err(Error::from);
+    let passphrase = if installed.is_ok() { passphrase } else { current };
+
+    remove_sidecars(&path);
+
+    let pool = connect(&path, passphrase.as_deref()).await?;
+    state.replace(pool, passphrase);
+
+    installed
+}
+
+fn check_passphrase(state: &DatabaseState, passphrase: &str) -> Result<(), Error> {
+    match state.passphrase() {
+        None => Err(Error::Validation("The database is not encrypted".to_string())),
+        Some(current) if current != passphrase => Err(Error::Validation("Incorrect passphrase".to_string())),
+        Some(_) => Ok(())
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), Error> {
+    if passphrase.is_empty() {
+        return Err(Error::Validation("Passphrase cannot be empty".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Whether the database is encrypted and waiting for `unlock_database`.
+/// Until it is unlocked, every command that reads or writes data fails.
+#[tauri::command]
+pub async fn is_database_locked(
+    app: AppHandle,
+) -> Result<bool, Error> {
+    Ok(app.try_state::<DatabaseState>().is_none())
+}
+
+#[tauri::command]
+pub async fn unlock_database(
+    app: AppHandle,
+    passphrase: String,
+) -> Result<(), Error> {
+    if app.try_state::<DatabaseState>().is_some() {
+        return Err(Error::Conflict("The database is already unlocked".to_string()));
+    }
+
+    let pool = connect(&database_path(&app)?, Some(&passphrase))
+        .await
+        .map_err(unlock_error)?;
+
+    open_library(&app, pool, Some(passphrase)).await;
+
+    Ok(())
+}
+
+/// Encrypts the open profile's database. Backups taken before this are
+/// left as they are.
+#[tauri::command]
+pub async fn enable_encryption(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+    passphrase: String,
+) -> Result<(), Error> {
+    if state.passphrase().is_some() {
+        return Err(Error::Conflict("The database is already encrypted".to_string()));
+    }
+
+    validate_passphrase(&passphrase)?;
+    reencrypt(&app, &state, Some(passphrase)).await
+}
+
+#[tauri::command]
+pub async fn change_passphrase(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+    current: String,
+    passphrase: String,
+) -> Result<(), Error> {
+    check_passphrase(&state, &current)?;
+    validate_passphrase(&passphrase)?;
+    reencrypt(&app, &state, Some(passphrase)).await
+}
+
+#[tauri::command]
+pub async fn disable_encryption(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+    passphrase: String,
+) -> Result<(), Error> {
+    check_passphrase(&state, &passphrase)?;
+    reencrypt(&app, &state, None).await
+}
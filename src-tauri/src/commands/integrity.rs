@@ -0,0 +1,21 @@
+use mnemona_core::integrity;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{IntegrityIssue, IntegrityIssueKind};
+
+#[tauri::command]
+pub async fn check_integrity(
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<IntegrityIssue>, Error> {
+    integrity::check_integrity(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn repair_integrity(
+    state: State<'_, DatabaseState>,
+    kinds: Vec<IntegrityIssueKind>,
+) -> Result<Vec<IntegrityIssue>, Error> {
+    integrity::repair_integrity(&state.pool(), kinds).await
+}
@@ -0,0 +1,19 @@
+use mnemona_core::journal;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+
+#[tauri::command]
+pub async fn undo(
+    state: State<'_, DatabaseState>,
+) -> Result<Option<String>, Error> {
+    journal::undo(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn redo(
+    state: State<'_, DatabaseState>,
+) -> Result<Option<String>, Error> {
+    journal::redo(&state.pool()).await
+}
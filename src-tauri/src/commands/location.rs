@@ -0,0 +1,121 @@
+use mnemona_core::db::connect;
+use mnemona_core::paths::ROOT_ENTRIES;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::db::{database_path, DatabaseState};
+use crate::error::Error;
+use crate::location::{resolve, set_pointer};
+use crate::types::{DataDirectory, DataDirectorySource};
+
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(from, to)?;
+    }
+
+    Ok(())
+}
+
+fn remove_entries(root: &Path) {
+    for name in ROOT_ENTRIES {
+        let path = root.join(name);
+
+        let _ = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+    }
+}
+
+fn copy_entries(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to)?;
+
+    for name in ROOT_ENTRIES {
+        let source = from.join(name);
+
+        if source.exists() {
+            copy_recursive(&source, &to.join(name))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_data_directory(
+    app: AppHandle,
+) -> Result<DataDirectory, Error> {
+    resolve(&app)
+}
+
+/// Moves every profile to `path` and keeps using it from then on. The
+/// database is closed while its files are copied, and the old copy is only
+/// removed once the new one has opened. A location forced by the command
+/// line, the environment or portable mode cannot be moved from here.
+#[tauri::command]
+pub async fn move_data_directory(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+    path: PathBuf,
+) -> Result<DataDirectory, Error> {
+    let current = resolve(&app)?;
+
+    if !matches!(current.source, DataDirectorySource::Moved | DataDirectorySource::Default) {
+        return Err(Error::Conflict("The data directory is set at launch and cannot be moved".to_string()));
+    }
+
+    if !path.is_absolute() {
+        return Err(Error::Validation("The data directory must be an absolute path".to_string()));
+    }
+
+    if path == current.path {
+        return Ok(current);
+    }
+
+    if path.starts_with(&current.path) {
+        return Err(Error::Validation("Cannot move the data directory inside itself".to_string()));
+    }
+
+    if ROOT_ENTRIES.iter().any(|name| path.join(name).exists()) {
+        return Err(Error::Conflict("The destination already holds Mnemona data".to_string()));
+    }
+
+    let passphrase = state.passphrase();
+    let pool = state.pool();
+    pool.close().await;
+
+    let moved = async {
+        copy_entries(&current.path, &path)?;
+        set_pointer(&app, &path)?;
+        connect(&database_path(&app)?, passphrase.as_deref())
+            .await
+            .map_err(Error::from)
+    }
+    .await;
+
+    match moved {
+        Ok(pool) => {
+            state.replace(pool, passphrase);
+            remove_entries(&current.path);
+            resolve(&app)
+        }
+        Err(e) => {
+            remove_entries(&path);
+            set_pointer(&app, &current.path)?;
+
+            let pool = connect(&database_path(&app)?, passphrase.as_deref()).await?;
+            state.replace(pool, passphrase);
+
+            Err(e)
+        }
+    }
+}
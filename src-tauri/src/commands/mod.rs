@@ -1,6 +1,20 @@
+pub mod backups;
 pub mod courses;
 pub mod departments;
+pub mod encryption;
+pub mod integrity;
+pub mod journal;
+pub mod location;
+pub mod notes;
+pub mod pomodoro;
+pub mod profiles;
 pub mod schedule;
+pub mod search;
+pub mod sessions;
 pub mod settings;
+pub mod tags;
 pub mod targets;
+pub mod terms;
+pub mod trash;
+pub mod versions;
 pub mod weeks;
@@ -0,0 +1,48 @@
+use mnemona_core::notes;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{Note, NoteOwner, NoteSearchHit};
+
+#[tauri::command]
+pub async fn create_note(
+    state: State<'_, DatabaseState>,
+    owner: NoteOwner,
+    body: String,
+) -> Result<Note, Error> {
+    notes::create_note(&state.pool(), owner, body).await
+}
+
+#[tauri::command]
+pub async fn get_notes(
+    state: State<'_, DatabaseState>,
+    owner: NoteOwner,
+) -> Result<Vec<Note>, Error> {
+    notes::get_notes(&state.pool(), owner).await
+}
+
+#[tauri::command]
+pub async fn update_note(
+    state: State<'_, DatabaseState>,
+    note_id: String,
+    body: String,
+) -> Result<(), Error> {
+    notes::update_note(&state.pool(), note_id, body).await
+}
+
+#[tauri::command]
+pub async fn delete_note(
+    state: State<'_, DatabaseState>,
+    note_id: String,
+) -> Result<(), Error> {
+    notes::delete_note(&state.pool(), note_id).await
+}
+
+#[tauri::command]
+pub async fn search_notes(
+    state: State<'_, DatabaseState>,
+    query: String,
+) -> Result<Vec<NoteSearchHit>, Error> {
+    notes::search_notes(&state.pool(), query).await
+}
@@ -0,0 +1,220 @@
+use chrono::{Duration, Utc};
+use mnemona_core::targets::ensure_target;
+use sqlx::SqlitePool;
+use std::sync::{Arc, Mutex};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::commands::settings::{load_settings, settings_path, PomodoroSettings};
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{PomodoroPhase, PomodoroStatus};
+
+/// Emitted with an `Option<PomodoroStatus>` whenever the timer changes phase,
+/// starts or stops.
+pub const POMODORO_EVENT: &str = "pomodoro";
+
+/// Emitted with a serialized `Error` when a finished work phase cannot be logged, e.g.
+/// because its target was deleted. The timer stops when this is sent.
+pub const POMODORO_ERROR_EVENT: &str = "pomodoro-error";
+
+struct Timer {
+    id: Uuid,
+    status: PomodoroStatus,
+    task: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+pub struct PomodoroState(Arc<Mutex<Option<Timer>>>);
+
+fn phase_minutes(phase: PomodoroPhase, settings: &PomodoroSettings) -> u64 {
+    match phase {
+        PomodoroPhase::Work => settings.work_minutes,
+        PomodoroPhase::ShortBreak => settings.short_break_minutes,
+        PomodoroPhase::LongBreak => settings.long_break_minutes,
+    }
+}
+
+fn next_phase(status: &PomodoroStatus, settings: &PomodoroSettings) -> PomodoroStatus {
+    let (phase, completed) = match status.phase {
+        PomodoroPhase::Work => {
+            let completed = status.completed + 1;
+
+            if completed.is_multiple_of(settings.long_break_every) {
+                (PomodoroPhase::LongBreak, completed)
+            } else {
+                (PomodoroPhase::ShortBreak, completed)
+            }
+        }
+        PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => {
+            (PomodoroPhase::Work, status.completed)
+        }
+    };
+
+    let now = Utc::now();
+
+    PomodoroStatus {
+        target_id: status.target_id.clone(),
+        phase,
+        phase_started_at: now,
+        phase_ends_at: now + Duration::minutes(phase_minutes(phase, settings) as i64),
+        completed,
+    }
+}
+
+async fn log_pomodoro(pool: &SqlitePool, status: &PomodoroStatus) -> Result<(), Error> {
+    ensure_target(pool, &status.target_id).await?;
+
+    let duration = (status.phase_ends_at - status.phase_started_at).num_seconds();
+
+    sqlx::query(
+        r#"
+        INSERT INTO study_sessions
+        (id, target_id, started_at, ended_at, duration_seconds, is_pomodoro)
+        VALUES (?, ?, ?, ?, ?, TRUE)
+        "#
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&status.target_id)
+    .bind(status.phase_started_at)
+    .bind(status.phase_ends_at)
+    .bind(duration)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn run_timer(
+    app: AppHandle,
+    timer: Arc<Mutex<Option<Timer>>>,
+    settings: PomodoroSettings,
+    id: Uuid,
+) {
+    loop {
+        let ends_at = match timer.lock().unwrap().as_ref() {
+            Some(t) if t.id == id => t.status.phase_ends_at,
+            _ => return,
+        };
+
+        // Sleep in bounded steps against the wall clock so the phase still
+        // ends on time after the machine wakes from suspend.
+        loop {
+            let remaining = ends_at - Utc::now();
+            if remaining <= Duration::zero() {
+                break;
+            }
+
+            let step = remaining.min(Duration::seconds(30)).to_std().unwrap_or_default();
+            tokio::time::sleep(step).await;
+        }
+
+        let (finished, next) = {
+            let mut guard = timer.lock().unwrap();
+            let Some(t) = guard.as_mut().filter(|t| t.id == id) else {
+                return;
+            };
+
+            let finished = t.status.clone();
+            t.status = next_phase(&finished, &settings);
+            (finished, t.status.clone())
+        };
+
+        if finished.phase == PomodoroPhase::Work {
+            if let Some(state) = app.try_state::<DatabaseState>() {
+                if let Err(e) = log_pomodoro(&state.pool(), &finished).await {
+                    {
+                        let mut guard = timer.lock().unwrap();
+                        if guard.as_ref().is_some_and(|t| t.id == id) {
+                            *guard = None;
+                        }
+                    }
+
+                    if let Ok(error) = serde_json::to_value(&e) {
+                        let _ = app.emit(POMODORO_ERROR_EVENT, error);
+                    }
+                    let _ = app.emit(POMODORO_EVENT, None::<PomodoroStatus>);
+                    return;
+                }
+            }
+        }
+
+        let _ = app.emit(POMODORO_EVENT, Some(next));
+    }
+}
+
+#[tauri::command]
+pub async fn start_pomodoro(
+    app: AppHandle,
+    state: State<'_, DatabaseState>,
+    pomodoro: State<'_, PomodoroState>,
+    target_id: String,
+) -> Result<PomodoroStatus, Error> {
+    ensure_target(&state.pool(), &target_id).await?;
+
+    let settings = load_settings(&settings_path(&app)?)?.pomodoro;
+    let now = Utc::now();
+
+    let status = PomodoroStatus {
+        target_id,
+        phase: PomodoroPhase::Work,
+        phase_started_at: now,
+        phase_ends_at: now + Duration::minutes(settings.work_minutes as i64),
+        completed: 0,
+    };
+
+    // Install the timer before spawning its task and attach the task after,
+    // so the lock is never held across the spawn.
+    let id = Uuid::new_v4();
+    let previous = pomodoro.0.lock().unwrap().replace(Timer {
+        id,
+        status: status.clone(),
+        task: None,
+    });
+
+    if let Some(task) = previous.and_then(|t| t.task) {
+        task.abort();
+    }
+
+    let task = tauri::async_runtime::spawn(run_timer(
+        app.clone(),
+        pomodoro.0.clone(),
+        settings,
+        id,
+    ));
+
+    match pomodoro.0.lock().unwrap().as_mut().filter(|t| t.id == id) {
+        Some(timer) => timer.task = Some(task),
+        None => task.abort(),
+    }
+
+    let _ = app.emit(POMODORO_EVENT, Some(status.clone()));
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn stop_pomodoro(
+    app: AppHandle,
+    pomodoro: State<'_, PomodoroState>,
+) -> Result<(), Error> {
+    let previous = pomodoro.0.lock().unwrap().take();
+
+    if let Some(previous) = previous {
+        if let Some(task) = previous.task {
+            task.abort();
+        }
+        let _ = app.emit(POMODORO_EVENT, None::<PomodoroStatus>);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_pomodoro(
+    pomodoro: State<'_, PomodoroState>,
+) -> Result<Option<PomodoroStatus>, Error> {
+    let guard = pomodoro.0.lock().unwrap();
+    Ok(guard.as_ref().map(|t| t.status.clone()))
+}
@@ -0,0 +1,189 @@
+use chrono::Utc;
+use mnemona_core::db::{connect, is_encrypted, unlock_error};
+use mnemona_core::paths::{self, ProfileEntry, Registry};
+use std::fs;
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::commands::encryption::open_library;
+use crate::commands::pomodoro::{stop_pomodoro, PomodoroState};
+use crate::commands::trash::purge_expired;
+use crate::db::DatabaseState;
+use crate::error::{report, BackgroundTask, Error};
+use crate::profiles::{load_registry, root_dir, save_registry};
+use crate::types::Profile;
+
+/// Emitted with the id of the newly opened profile after a switch, so every
+/// view can reload its data.
+pub const PROFILE_EVENT: &str = "profile";
+
+fn to_profile(entry: &ProfileEntry, registry: &Registry) -> Profile {
+    Profile {
+        id: entry.id.clone(),
+        name: entry.name.clone(),
+        is_active: entry.id == registry.active,
+        created_at: entry.created_at
+    }
+}
+
+fn validate_name(registry: &Registry, name: &str, except_id: Option<&str>) -> Result<String, Error> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(Error::Validation("Profile name cannot be empty".to_string()));
+    }
+
+    let taken = registry
+        .profiles
+        .iter()
+        .any(|p| Some(p.id.as_str()) != except_id && p.name.eq_ignore_ascii_case(name));
+
+    if taken {
+        return Err(Error::Conflict(format!("A profile named {} already exists", name)));
+    }
+
+    Ok(name.to_string())
+}
+
+#[tauri::command]
+pub async fn list_profiles(
+    app: AppHandle,
+) -> Result<Vec<Profile>, Error> {
+    let registry = load_registry(&app)?;
+
+    Ok(registry
+        .profiles
+        .iter()
+        .map(|entry| to_profile(entry, &registry))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn create_profile(
+    app: AppHandle,
+    name: String,
+) -> Result<Profile, Error> {
+    let mut registry = load_registry(&app)?;
+    let name = validate_name(&registry, &name, None)?;
+
+    let entry = ProfileEntry {
+        id: Uuid::new_v4().to_string(),
+        name,
+        created_at: Utc::now()
+    };
+
+    fs::create_dir_all(paths::profile_dir(&root_dir(&app)?, &entry.id))?;
+
+    registry.profiles.push(entry.clone());
+    save_registry(&app, &registry)?;
+
+    Ok(to_profile(&entry, &registry))
+}
+
+#[tauri::command]
+pub async fn rename_profile(
+    app: AppHandle,
+    profile_id: String,
+    name: String,
+) -> Result<(), Error> {
+    let mut registry = load_registry(&app)?;
+    let name = validate_name(&registry, &name, Some(&profile_id))?;
+
+    let entry = registry
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or(Error::NotFound("Profile not found".to_string()))?;
+
+    entry.name = name;
+    save_registry(&app, &registry)
+}
+
+/// Deletes a profile along with its database, settings and backups. The open
+/// profile cannot be deleted; switch away from it first.
+#[tauri::command]
+pub async fn delete_profile(
+    app: AppHandle,
+    profile_id: String,
+) -> Result<(), Error> {
+    let mut registry = load_registry(&app)?;
+
+    if registry.active == profile_id {
+        return Err(Error::Conflict("Cannot delete the open profile".to_string()));
+    }
+
+    let before = registry.profiles.len();
+    registry.profiles.retain(|p| p.id != profile_id);
+
+    if registry.profiles.len() == before {
+        return Err(Error::NotFound("Profile not found".to_string()));
+    }
+
+    save_registry(&app, &registry)?;
+
+    let dir = paths::profile_dir(&root_dir(&app)?, &profile_id);
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+
+    Ok(())
+}
+
+/// Opens another profile in place of the current one. The new database is
+/// opened and migrated before anything changes, so a failure leaves the
+/// current profile open. An encrypted profile needs its `passphrase`. A
+/// running pomodoro belongs to the old profile and is stopped. This also
+/// works while the current profile is still locked, in which case the new
+/// one is opened as if it had been unlocked.
+#[tauri::command]
+pub async fn switch_profile(
+    app: AppHandle,
+    pomodoro: State<'_, PomodoroState>,
+    profile_id: String,
+    passphrase: Option<String>,
+) -> Result<(), Error> {
+    let mut registry = load_registry(&app)?;
+
+    if registry.active == profile_id {
+        return Ok(());
+    }
+
+    if !registry.profiles.iter().any(|p| p.id == profile_id) {
+        return Err(Error::NotFound("Profile not found".to_string()));
+    }
+
+    let dir = paths::profile_dir(&root_dir(&app)?, &profile_id);
+    fs::create_dir_all(&dir)?;
+
+    let path = paths::database_path(&dir);
+    let passphrase = if is_encrypted(&path) {
+        Some(passphrase.ok_or(Error::Validation("This profile is encrypted and needs a passphrase".to_string()))?)
+    } else {
+        None
+    };
+
+    let pool = connect(&path, passphrase.as_deref())
+        .await
+        .map_err(unlock_error)?;
+
+    stop_pomodoro(app.clone(), pomodoro).await?;
+
+    registry.active = profile_id.clone();
+    save_registry(&app, &registry)?;
+
+    match app.try_state::<DatabaseState>() {
+        Some(state) => {
+            let previous = state.replace(pool.clone(), passphrase);
+            previous.close().await;
+
+            if let Err(e) = purge_expired(&app, &pool).await {
+                report(&app, BackgroundTask::PurgeTrash, &e);
+            }
+        }
+        None => open_library(&app, pool, passphrase).await,
+    }
+
+    let _ = app.emit(PROFILE_EVENT, profile_id);
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use mnemona_core::search;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{SearchFilters, SearchHit};
+
+#[tauri::command]
+pub async fn search(
+    state: State<'_, DatabaseState>,
+    query: String,
+    filters: SearchFilters,
+) -> Result<Vec<SearchHit>, Error> {
+    search::search(&state.pool(), query, filters).await
+}
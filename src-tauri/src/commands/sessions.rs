@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use mnemona_core::sessions;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::StudySession;
+
+#[tauri::command]
+pub async fn start_session(
+    state: State<'_, DatabaseState>,
+    target_id: String,
+) -> Result<StudySession, Error> {
+    sessions::start_session(&state.pool(), target_id).await
+}
+
+#[tauri::command]
+pub async fn stop_session(
+    state: State<'_, DatabaseState>,
+    session_id: String,
+) -> Result<StudySession, Error> {
+    sessions::stop_session(&state.pool(), session_id).await
+}
+
+#[tauri::command]
+pub async fn log_session(
+    state: State<'_, DatabaseState>,
+    target_id: String,
+    started_at: DateTime<Utc>,
+    minutes: i64,
+) -> Result<StudySession, Error> {
+    sessions::log_session(&state.pool(), target_id, started_at, minutes).await
+}
@@ -1,46 +1,100 @@
+use chrono::{NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
+
+use crate::error::Error;
+use crate::profiles::profile_dir;
 
 #[derive(Serialize, Deserialize, Default)]
-struct Settings {
+pub(crate) struct Settings {
+    #[serde(default)]
+    pub(crate) llm_token: Option<String>,
+    #[serde(default)]
+    pub(crate) weekly_capacity_minutes: Option<i64>,
+    #[serde(default)]
+    pub(crate) pomodoro: PomodoroSettings,
+    #[serde(default)]
+    pub(crate) reminders: Vec<ReminderRule>,
+    #[serde(default)]
+    pub(crate) trash_retention_days: Option<i64>,
     #[serde(default)]
-    llm_token: Option<String>,
+    pub(crate) backup_retention: BackupRetention,
 }
 
-fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| e.to_string())?;
+pub(crate) const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
 
-    fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PomodoroSettings {
+    pub work_minutes: u64,
+    pub short_break_minutes: u64,
+    pub long_break_minutes: u64,
+    pub long_break_every: u64,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+        }
+    }
+}
 
-    Ok(app_data_dir.join("settings.json"))
+/// How many automatic backups to keep: the newest one from each of the last
+/// `daily` days, `weekly` ISO weeks and `monthly` months.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRetention {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
 }
 
-fn load_settings(path: &Path) -> Result<Settings, String> {
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            daily: 7,
+            weekly: 4,
+            monthly: 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderRule {
+    pub weekday: Weekday,
+    pub time: NaiveTime,
+}
+
+pub(crate) fn settings_path(app: &AppHandle) -> Result<PathBuf, Error> {
+    Ok(profile_dir(app)?.join("settings.json"))
+}
+
+pub(crate) fn load_settings(path: &Path) -> Result<Settings, Error> {
     if !path.exists() {
         return Ok(Settings::default());
     }
 
-    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let settings: Settings = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+    let contents = fs::read_to_string(path)?;
+    let settings: Settings = serde_json::from_str(&contents)?;
     Ok(settings)
 }
 
-fn save_settings(path: &Path, settings: &Settings) -> Result<(), String> {
+pub(crate) fn save_settings(path: &Path, settings: &Settings) -> Result<(), Error> {
     let contents =
-        serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(path, contents).map_err(|e| e.to_string())
+        serde_json::to_string_pretty(settings)?;
+    fs::write(path, contents).map_err(Error::from)
 }
 
 #[tauri::command]
 pub async fn set_llm_token(
     app: AppHandle,
     token: String,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     let path = settings_path(&app)?;
     let mut settings = load_settings(&path)?;
     settings.llm_token = Some(token);
@@ -50,9 +104,126 @@ pub async fn set_llm_token(
 #[tauri::command]
 pub async fn get_llm_token(
     app: AppHandle,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, Error> {
     let path = settings_path(&app)?;
     let settings = load_settings(&path)?;
     Ok(settings.llm_token)
 }
 
+#[tauri::command]
+pub async fn set_weekly_capacity(
+    app: AppHandle,
+    minutes: Option<i64>,
+) -> Result<(), Error> {
+    if minutes.is_some_and(|m| m <= 0) {
+        return Err(Error::Validation("weekly capacity must be positive".to_string()));
+    }
+
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path)?;
+    settings.weekly_capacity_minutes = minutes;
+    save_settings(&path, &settings)
+}
+
+#[tauri::command]
+pub async fn get_weekly_capacity(
+    app: AppHandle,
+) -> Result<Option<i64>, Error> {
+    let path = settings_path(&app)?;
+    let settings = load_settings(&path)?;
+    Ok(settings.weekly_capacity_minutes)
+}
+
+#[tauri::command]
+pub async fn set_pomodoro_settings(
+    app: AppHandle,
+    pomodoro: PomodoroSettings,
+) -> Result<(), Error> {
+    if pomodoro.work_minutes == 0 || pomodoro.long_break_every == 0 {
+        return Err(Error::Validation("work length and long break interval must be positive".to_string()));
+    }
+
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path)?;
+    settings.pomodoro = pomodoro;
+    save_settings(&path, &settings)
+}
+
+#[tauri::command]
+pub async fn get_pomodoro_settings(
+    app: AppHandle,
+) -> Result<PomodoroSettings, Error> {
+    let path = settings_path(&app)?;
+    let settings = load_settings(&path)?;
+    Ok(settings.pomodoro)
+}
+
+#[tauri::command]
+pub async fn set_reminders(
+    app: AppHandle,
+    reminders: Vec<ReminderRule>,
+) -> Result<(), Error> {
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path)?;
+    settings.reminders = reminders;
+    save_settings(&path, &settings)
+}
+
+#[tauri::command]
+pub async fn get_reminders(
+    app: AppHandle,
+) -> Result<Vec<ReminderRule>, Error> {
+    let path = settings_path(&app)?;
+    let settings = load_settings(&path)?;
+    Ok(settings.reminders)
+}
+
+#[tauri::command]
+pub async fn set_trash_retention(
+    app: AppHandle,
+    days: i64,
+) -> Result<(), Error> {
+    if days < 1 {
+        return Err(Error::Validation("trash retention must be at least one day".to_string()));
+    }
+
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path)?;
+    settings.trash_retention_days = Some(days);
+    save_settings(&path, &settings)
+}
+
+#[tauri::command]
+pub async fn get_trash_retention(
+    app: AppHandle,
+) -> Result<i64, Error> {
+    let path = settings_path(&app)?;
+    let settings = load_settings(&path)?;
+    Ok(settings
+        .trash_retention_days
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS))
+}
+
+#[tauri::command]
+pub async fn set_backup_retention(
+    app: AppHandle,
+    retention: BackupRetention,
+) -> Result<(), Error> {
+    if retention.daily == 0 {
+        return Err(Error::Validation("at least one daily backup must be kept".to_string()));
+    }
+
+    let path = settings_path(&app)?;
+    let mut settings = load_settings(&path)?;
+    settings.backup_retention = retention;
+    save_settings(&path, &settings)
+}
+
+#[tauri::command]
+pub async fn get_backup_retention(
+    app: AppHandle,
+) -> Result<BackupRetention, Error> {
+    let path = settings_path(&app)?;
+    let settings = load_settings(&path)?;
+    Ok(settings.backup_retention)
+}
@@ -0,0 +1,64 @@
+use mnemona_core::tags;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{Tag, TagDraft, TaggedItem};
+
+#[tauri::command]
+pub async fn get_tags(
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<Tag>, Error> {
+    tags::get_tags(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn get_item_tags(
+    state: State<'_, DatabaseState>,
+    item: TaggedItem,
+) -> Result<Vec<Tag>, Error> {
+    tags::get_item_tags(&state.pool(), item).await
+}
+
+#[tauri::command]
+pub async fn create_tag(
+    state: State<'_, DatabaseState>,
+    tag: TagDraft,
+) -> Result<Tag, Error> {
+    tags::create_tag(&state.pool(), tag).await
+}
+
+#[tauri::command]
+pub async fn update_tag(
+    state: State<'_, DatabaseState>,
+    tag_id: String,
+    tag: TagDraft,
+) -> Result<(), Error> {
+    tags::update_tag(&state.pool(), tag_id, tag).await
+}
+
+#[tauri::command]
+pub async fn delete_tag(
+    state: State<'_, DatabaseState>,
+    tag_id: String,
+) -> Result<(), Error> {
+    tags::delete_tag(&state.pool(), tag_id).await
+}
+
+#[tauri::command]
+pub async fn attach_tag(
+    state: State<'_, DatabaseState>,
+    tag_id: String,
+    item: TaggedItem,
+) -> Result<(), Error> {
+    tags::attach_tag(&state.pool(), tag_id, item).await
+}
+
+#[tauri::command]
+pub async fn detach_tag(
+    state: State<'_, DatabaseState>,
+    tag_id: String,
+    item: TaggedItem,
+) -> Result<(), Error> {
+    tags::detach_tag(&state.pool(), tag_id, item).await
+}
@@ -0,0 +1,46 @@
+use mnemona_core::terms;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{Term, TermDraft, TermOverview};
+
+#[tauri::command]
+pub async fn get_terms(
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<Term>, Error> {
+    terms::get_terms(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn create_term(
+    state: State<'_, DatabaseState>,
+    term: TermDraft,
+) -> Result<Term, Error> {
+    terms::create_term(&state.pool(), term).await
+}
+
+#[tauri::command]
+pub async fn update_term(
+    state: State<'_, DatabaseState>,
+    term_id: String,
+    term: TermDraft,
+) -> Result<(), Error> {
+    terms::update_term(&state.pool(), term_id, term).await
+}
+
+#[tauri::command]
+pub async fn delete_term(
+    state: State<'_, DatabaseState>,
+    term_id: String,
+) -> Result<(), Error> {
+    terms::delete_term(&state.pool(), term_id).await
+}
+
+#[tauri::command]
+pub async fn get_term_overview(
+    state: State<'_, DatabaseState>,
+    term_id: String,
+) -> Result<TermOverview, Error> {
+    terms::get_term_overview(&state.pool(), term_id).await
+}
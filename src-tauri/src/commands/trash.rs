@@ -0,0 +1,41 @@
+use mnemona_core::trash;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::commands::settings::{load_settings, settings_path, DEFAULT_TRASH_RETENTION_DAYS};
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::TrashedCourse;
+
+/// Purges courses that have been in the trash longer than the configured
+/// retention period. Runs whenever a library is opened.
+pub async fn purge_expired(app: &AppHandle, pool: &SqlitePool) -> Result<(), Error> {
+    let days = load_settings(&settings_path(app)?)?
+        .trash_retention_days
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+
+    trash::purge_expired(pool, days).await
+}
+
+#[tauri::command]
+pub async fn list_trash(
+    state: State<'_, DatabaseState>,
+) -> Result<Vec<TrashedCourse>, Error> {
+    trash::list_trash(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn restore_course(
+    state: State<'_, DatabaseState>,
+    course_id: String,
+) -> Result<(), Error> {
+    trash::restore_course(&state.pool(), course_id).await
+}
+
+#[tauri::command]
+pub async fn purge_trash(
+    state: State<'_, DatabaseState>,
+    course_id: Option<String>,
+) -> Result<(), Error> {
+    trash::purge_trash(&state.pool(), course_id).await
+}
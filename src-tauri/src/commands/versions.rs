@@ -0,0 +1,31 @@
+use mnemona_core::versions;
+use tauri::State;
+
+use crate::db::DatabaseState;
+use crate::error::Error;
+use crate::types::{CourseDiff, CourseVersion};
+
+#[tauri::command]
+pub async fn list_course_versions(
+    state: State<'_, DatabaseState>,
+    course_id: String,
+) -> Result<Vec<CourseVersion>, Error> {
+    versions::list_course_versions(&state.pool(), course_id).await
+}
+
+#[tauri::command]
+pub async fn diff_course_versions(
+    state: State<'_, DatabaseState>,
+    from_version_id: String,
+    to_version_id: String,
+) -> Result<CourseDiff, Error> {
+    versions::diff_course_versions(&state.pool(), from_version_id, to_version_id).await
+}
+
+#[tauri::command]
+pub async fn revert_course_version(
+    state: State<'_, DatabaseState>,
+    version_id: String,
+) -> Result<(), Error> {
+    versions::revert_course_version(&state.pool(), version_id).await
+}
@@ -1,28 +1,62 @@
-use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
-use tauri::{AppHandle, Manager};
+use mnemona_core::db::connect;
+use mnemona_core::paths;
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::AppHandle;
+
+use crate::error::Error;
+use crate::profiles::profile_dir;
 
 pub struct Database {
     pub pool: SqlitePool
 }
 
-pub struct DatabaseState(pub SqlitePool);
+struct Connection {
+    pool: SqlitePool,
+    passphrase: Option<String>
+}
+
+/// The pool shared by all commands, and the passphrase it was opened with
+/// when the database is encrypted. It sits behind a lock so a restored
+/// backup or another profile can replace it while the app is running;
+/// commands take a cheap clone of the current pool at the start of each
+/// call.
+pub struct DatabaseState(RwLock<Connection>);
+
+impl DatabaseState {
+    pub fn new(pool: SqlitePool, passphrase: Option<String>) -> Self {
+        Self(RwLock::new(Connection { pool, passphrase }))
+    }
+
+    pub fn pool(&self) -> SqlitePool {
+        self.0.read().unwrap().pool.clone()
+    }
+
+    pub fn passphrase(&self) -> Option<String> {
+        self.0.read().unwrap().passphrase.clone()
+    }
+
+    /// Installs `pool` and returns the one it replaced.
+    pub fn replace(&self, pool: SqlitePool, passphrase: Option<String>) -> SqlitePool {
+        let previous = std::mem::replace(
+            &mut *self.0.write().unwrap(),
+            Connection { pool, passphrase }
+        );
+
+        previous.pool
+    }
+}
+
+/// The database file of the open profile.
+pub fn database_path(handle: &AppHandle) -> Result<PathBuf, Error> {
+    Ok(paths::database_path(&profile_dir(handle)?))
+}
 
 impl Database {
-    pub async fn new(handle: &AppHandle) -> Result<Self, sqlx::Error> {
-        let app_data_dir = handle
-            .path()
-            .app_data_dir()
-            .expect("failed to get app data dir");
-        std::fs::create_dir_all(&app_data_dir)
-            .expect("failed to create app data dir");
-
-        let db_path = app_data_dir.join("mnemona.db");
-        let options = SqliteConnectOptions::new()
-            .filename(db_path)
-            .create_if_missing(true);
-        let pool = SqlitePool::connect_with(options).await?;
-
-        sqlx::migrate!("./migrations").run(&pool).await?;
+    pub async fn new(handle: &AppHandle, passphrase: Option<&str>) -> Result<Self, sqlx::Error> {
+        let path = database_path(handle).expect("failed to resolve database path");
+        let pool = connect(&path, passphrase).await?;
 
         Ok(Self { pool })
     }
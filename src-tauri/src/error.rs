@@ -0,0 +1,29 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+pub use mnemona_core::error::Error;
+
+/// Emitted with a `BackgroundError` when work the app starts on its own,
+/// like the startup backup, fails, since no command is there to return the
+/// error to.
+pub const BACKGROUND_ERROR_EVENT: &str = "background-error";
+
+/// Something the app does without being asked.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackgroundTask {
+    Backup,
+    PurgeTrash,
+    Reminders
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BackgroundError<'a> {
+    task: BackgroundTask,
+    error: &'a Error
+}
+
+/// Tells the UI that `task` failed with `error`.
+pub fn report(app: &AppHandle, task: BackgroundTask, error: &Error) {
+    let _ = app.emit(BACKGROUND_ERROR_EVENT, BackgroundError { task, error });
+}
@@ -1,17 +1,60 @@
+pub mod backups;
 pub mod commands;
 pub mod db;
+pub mod error;
+pub mod location;
+pub mod profiles;
+pub mod reminders;
 pub mod types;
 
-use tauri::Manager;
-
+use crate::commands::backups::{create_backup, list_backups, restore_backup};
 use crate::commands::courses::{
-    create_courses, delete_course, get_course, get_courses, update_course,
-    update_course_status,
+    create_courses, delete_course, get_course, get_course_by_code, get_courses,
+    get_focus_course, move_course, set_course_priority, set_course_term,
+    update_course, update_course_status,
+};
+use crate::commands::departments::{
+    create_department, delete_department, get_departments, merge_departments,
+    rename_department, renumber_department,
+};
+use crate::commands::encryption::{
+    change_passphrase, disable_encryption, enable_encryption,
+    is_database_locked, open_library, unlock_database,
+};
+use crate::commands::integrity::{check_integrity, repair_integrity};
+use crate::commands::journal::{redo, undo};
+use crate::commands::location::{get_data_directory, move_data_directory};
+use crate::commands::notes::{
+    create_note, delete_note, get_notes, search_notes, update_note,
+};
+use crate::commands::pomodoro::{
+    get_pomodoro, start_pomodoro, stop_pomodoro, PomodoroState,
+};
+use crate::commands::profiles::{
+    create_profile, delete_profile, list_profiles, rename_profile,
+    switch_profile,
 };
-use crate::commands::departments::get_departments;
 use crate::commands::schedule::get_schedule;
-use crate::commands::settings::{get_llm_token, set_llm_token};
+use crate::commands::search::search;
+use crate::commands::sessions::{log_session, start_session, stop_session};
+use crate::commands::settings::{
+    get_backup_retention, get_llm_token, get_pomodoro_settings, get_reminders,
+    get_trash_retention, get_weekly_capacity, set_backup_retention,
+    set_llm_token, set_pomodoro_settings, set_reminders, set_trash_retention,
+    set_weekly_capacity,
+};
+use crate::commands::tags::{
+    attach_tag, create_tag, delete_tag, detach_tag, get_item_tags, get_tags,
+    update_tag,
+};
 use crate::commands::targets::change_target_status;
+use crate::commands::terms::{
+    create_term, delete_term, get_term_overview, get_terms, update_term,
+};
+use crate::commands::trash::{list_trash, purge_trash, restore_course};
+use crate::commands::versions::{
+    diff_course_versions, list_course_versions, revert_course_version,
+};
 use crate::commands::weeks::change_week_status;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -21,27 +64,110 @@ pub fn run() {
             let handle = app.handle().clone();
 
             tauri::async_runtime::block_on(async move {
-                let database = db::Database::new(&handle)
+                let path = db::database_path(&handle)
+                    .expect("failed to resolve database path");
+
+                // An encrypted database stays closed until the frontend
+                // calls `unlock_database` with the passphrase.
+                if mnemona_core::db::is_encrypted(&path) {
+                    return;
+                }
+
+                let database = db::Database::new(&handle, None)
                     .await
                     .expect("failed to initialize database");
 
-                handle.manage(db::DatabaseState(database.pool));
+                open_library(&handle, database.pool, None).await;
             });
 
+            reminders::spawn(app.handle().clone());
+            backups::spawn(app.handle().clone());
+
             Ok(())
         })
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
+        .manage(PomodoroState::default())
         .invoke_handler(tauri::generate_handler![
             create_courses,
             get_course,
+            get_course_by_code,
             get_courses,
             update_course,
             update_course_status,
+            set_course_priority,
+            set_course_term,
+            get_focus_course,
             delete_course,
+            move_course,
+            list_course_versions,
+            diff_course_versions,
+            revert_course_version,
+            list_trash,
+            restore_course,
+            purge_trash,
             get_departments,
+            create_department,
+            rename_department,
+            delete_department,
+            merge_departments,
+            renumber_department,
+            check_integrity,
+            repair_integrity,
+            undo,
+            redo,
+            create_note,
+            get_notes,
+            update_note,
+            delete_note,
+            search_notes,
             get_schedule,
+            search,
+            start_session,
+            stop_session,
+            log_session,
+            start_pomodoro,
+            stop_pomodoro,
+            get_pomodoro,
+            list_profiles,
+            create_profile,
+            rename_profile,
+            delete_profile,
+            switch_profile,
             get_llm_token,
             set_llm_token,
+            get_weekly_capacity,
+            set_weekly_capacity,
+            get_pomodoro_settings,
+            set_pomodoro_settings,
+            get_reminders,
+            set_reminders,
+            get_trash_retention,
+            set_trash_retention,
+            get_backup_retention,
+            set_backup_retention,
+            is_database_locked,
+            unlock_database,
+            enable_encryption,
+            change_passphrase,
+            disable_encryption,
+            get_data_directory,
+            move_data_directory,
+            list_backups,
+            create_backup,
+            restore_backup,
+            get_tags,
+            get_item_tags,
+            create_tag,
+            update_tag,
+            delete_tag,
+            attach_tag,
+            detach_tag,
+            get_terms,
+            create_term,
+            update_term,
+            delete_term,
+            get_term_overview,
             change_target_status,
             change_week_status
         ])
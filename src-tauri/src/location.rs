@@ -0,0 +1,44 @@
+use mnemona_core::paths;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::error::Error;
+use crate::types::DataDirectory;
+
+const FLAG: &str = "--data-dir";
+
+/// The platform app data dir, where data lives unless told otherwise.
+fn default_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+/// Reads `--data-dir <path>` or `--data-dir=<path>` from the command line.
+fn from_flag() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == FLAG {
+            return args.next().map(PathBuf::from);
+        }
+
+        if let Some(value) = arg.strip_prefix(FLAG).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(PathBuf::from(value));
+        }
+    }
+
+    None
+}
+
+/// Where all profiles are stored. See `paths::resolve` for the order in which
+/// the flag and the other sources are tried.
+pub(crate) fn resolve(app: &AppHandle) -> Result<DataDirectory, Error> {
+    paths::resolve(from_flag(), &default_dir(app)?)
+}
+
+/// Records `data_dir` as the new home of the data, or forgets the override
+/// when it is the platform app data dir.
+pub(crate) fn set_pointer(app: &AppHandle, data_dir: &Path) -> Result<(), Error> {
+    paths::set_pointer(&default_dir(app)?, data_dir)
+}
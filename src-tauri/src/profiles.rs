@@ -0,0 +1,80 @@
+use chrono::Utc;
+use mnemona_core::paths::{self, read_registry, write_registry, ProfileEntry, Registry};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::Error;
+use crate::location;
+
+pub(crate) const DEFAULT_PROFILE_ID: &str = "default";
+
+/// Files that belonged to the single library used before profiles existed.
+const LEGACY_FILES: [&str; 5] = [
+    "mnemona.db",
+    "mnemona.db-wal",
+    "mnemona.db-shm",
+    "settings.json",
+    "backups",
+];
+
+pub(crate) fn root_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    let dir = location::resolve(app)?.path;
+
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+/// Moves a library from before profiles existed into the default profile.
+fn adopt_legacy(root: &Path) -> Result<(), Error> {
+    let target = paths::profile_dir(root, DEFAULT_PROFILE_ID);
+    fs::create_dir_all(&target)?;
+
+    for name in LEGACY_FILES {
+        let from = root.join(name);
+
+        if from.exists() {
+            fs::rename(&from, target.join(name))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn load_registry(app: &AppHandle) -> Result<Registry, Error> {
+    let root = root_dir(app)?;
+
+    if let Some(registry) = read_registry(&root)? {
+        return Ok(registry);
+    }
+
+    adopt_legacy(&root)?;
+
+    let registry = Registry {
+        active: DEFAULT_PROFILE_ID.to_string(),
+        profiles: vec![ProfileEntry {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Default".to_string(),
+            created_at: Utc::now(),
+        }],
+    };
+
+    save_registry(app, &registry)?;
+    Ok(registry)
+}
+
+pub(crate) fn save_registry(app: &AppHandle, registry: &Registry) -> Result<(), Error> {
+    write_registry(&root_dir(app)?, registry)
+}
+
+/// The directory holding the database, settings and backups of the open
+/// profile.
+pub(crate) fn profile_dir(app: &AppHandle) -> Result<PathBuf, Error> {
+    let registry = load_registry(app)?;
+    let dir = paths::profile_dir(&root_dir(app)?, &registry.active);
+
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
@@ -0,0 +1,176 @@
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime};
+use mnemona_core::courses::get_focus_course;
+use mnemona_core::schedule::{get_schedule, monday_of};
+use sqlx::{Row, SqlitePool};
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::commands::settings::{load_settings, settings_path, ReminderRule};
+use crate::db::DatabaseState;
+use crate::error::{report, BackgroundTask, Error};
+
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(30);
+const MAX_LISTED: usize = 8;
+
+pub struct Reminder {
+    pub title: String,
+    pub body: String,
+}
+
+/// Whether any rule fell due in the half-open window `(since, until]`.
+fn is_due(rules: &[ReminderRule], since: NaiveDateTime, until: NaiveDateTime) -> bool {
+    since
+        .date()
+        .iter_days()
+        .take_while(|day| *day <= until.date())
+        .any(|day| {
+            rules.iter().any(|rule| {
+                let at = day.and_time(rule.time);
+                day.weekday() == rule.weekday && since < at && at <= until
+            })
+        })
+}
+
+fn summarize(lines: Vec<String>) -> String {
+    let extra = lines.len().saturating_sub(MAX_LISTED);
+    let mut body = lines.into_iter().take(MAX_LISTED).collect::<Vec<_>>().join("\n");
+
+    if extra > 0 {
+        body.push_str(&format!("\n…and {} more", extra));
+    }
+
+    body
+}
+
+async fn overdue_weeks(pool: &SqlitePool, monday: NaiveDate) -> Result<Vec<String>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          d.code     AS dept_code,
+          c.serial   AS course_serial,
+          w.serial   AS week_serial
+        FROM weeks w
+        JOIN courses c ON w.course_id = c.id
+        JOIN departments d ON c.department_id = d.id
+        WHERE w.date < ?
+          AND w.is_complete = false
+          AND c.status = 'active'
+          AND c.deleted_at IS NULL
+        ORDER BY w.date, d.code, c.serial
+        "#
+    )
+    .bind(monday)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            format!(
+                "{} {} · Week {}",
+                row.get::<String, _>("dept_code"),
+                row.get::<i64, _>("course_serial"),
+                row.get::<i64, _>("week_serial")
+            )
+        })
+        .collect())
+}
+
+/// Builds the notifications for the week containing `today`: one listing
+/// the incomplete targets scheduled for this Monday, highest-priority
+/// courses first and headed by the focus course, and one for weeks from
+/// earlier Mondays that are still open.
+pub async fn compose(
+    pool: &SqlitePool,
+    today: NaiveDate,
+) -> Result<Vec<Reminder>, Error> {
+    let monday = monday_of(today);
+    let mut reminders = Vec::new();
+
+    let overdue = overdue_weeks(pool, monday).await?;
+
+    let schedule = get_schedule(pool, monday, Vec::new()).await?;
+
+    let pending: Vec<String> = schedule
+        .iter()
+        .flat_map(|item| {
+            item.weeks.iter().flat_map(move |week| {
+                week.targets
+                    .iter()
+                    .filter(|t| !t.is_complete)
+                    .map(move |t| {
+                        format!(
+                            "{} {} · {}",
+                            item.course.department, item.course.serial, t.text
+                        )
+                    })
+            })
+        })
+        .collect();
+
+    if !pending.is_empty() {
+        let title = format!("{} targets left this week", pending.len());
+        let mut body = summarize(pending);
+
+        if let Some(focus) = get_focus_course(pool).await? {
+            body = format!("Focus: {} {} · {}\n{}", focus.department, focus.serial, focus.name, body);
+        }
+
+        reminders.push(Reminder { title, body });
+    }
+
+    if !overdue.is_empty() {
+        reminders.push(Reminder {
+            title: format!("{} overdue weeks", overdue.len()),
+            body: summarize(overdue),
+        });
+    }
+
+    Ok(reminders)
+}
+
+async fn notify(app: &AppHandle) -> Result<(), Error> {
+    let Some(state) = app.try_state::<DatabaseState>() else {
+        return Ok(());
+    };
+    let reminders = compose(&state.pool(), Local::now().date_naive()).await?;
+
+    for reminder in reminders {
+        app.notification()
+            .builder()
+            .title(reminder.title)
+            .body(reminder.body)
+            .show()
+            .map_err(std::io::Error::other)?;
+    }
+
+    Ok(())
+}
+
+/// Starts the background task that fires reminder notifications at the times
+/// configured in settings. Missed reminders, e.g. while the app was closed,
+/// are not replayed.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_check = Local::now().naive_local();
+
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let now = Local::now().naive_local();
+            let rules = settings_path(&app)
+                .and_then(|path| load_settings(&path))
+                .map(|settings| settings.reminders)
+                .unwrap_or_default();
+
+            if is_due(&rules, last_check, now) {
+                if let Err(e) = notify(&app).await {
+                    report(&app, BackgroundTask::Reminders, &e);
+                }
+            }
+
+            last_check = now;
+        }
+    });
+}
@@ -1,4 +1,6 @@
+import { useEffect, useState } from "react";
 import { Route, Routes } from "react-router-dom";
+import { invoke } from "@tauri-apps/api/core";
 
 import Layout from "./Layout";
 import Course from "./pages/Course";
@@ -6,10 +8,20 @@ import Courses from "./pages/Courses";
 import Dashboard from "./pages/Dashboard";
 import Generate from "./pages/Generate";
 import Settings from "./pages/Settings";
+import Unlock from "./pages/Unlock";
 
 import "./App.css";
 
 function App() {
+  const [locked, setLocked] = useState<boolean | null>(null);
+
+  useEffect(() => {
+    invoke<boolean>("is_database_locked").then(setLocked);
+  }, []);
+
+  if (locked === null) return null;
+  if (locked) return <Unlock onUnlock={() => setLocked(false)} />;
+
   return (
     <Routes>
       <Route element={<Layout />}>
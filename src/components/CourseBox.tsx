@@ -29,7 +29,7 @@ export default function CourseBox({ course, statusColorMap, deleteCourse }: { co
   }
 
   return (
-    <div onClick={() => navigate(`/courses/${course.id}`)} className="course-card-container">
+    <div onClick={() => navigate(`/courses/${course.department}-${course.serial}`)} className="course-card-container">
       {course.status !== "draft" && (
         <div className="blobs-wrapper">
           {blobs.map((style, index) => (
@@ -28,6 +28,7 @@ function Course() {
           serial: target.serial,
           text: target.text,
           source: target.source,
+          estimated_minutes: target.estimatedMinutes,
         })),
       })),
     };
@@ -60,12 +61,14 @@ The prompt field is a string that will be passed on to you in the future for gen
 Description should be short whereas prompt should be detailed.
 Book must preferably have a specific edition number listed.
 Source refers to a specific location within the book which where content for the target is located.
+Estimated minutes is a realistic estimate of the study time a target needs, including reading and exercises.
 Ensure that there is no overlap in content between different courses.
 
 TargetDraft: {
   serial: number,
   text: string,
-  source: string
+  source: string,
+  estimated_minutes: number
 }
 
 WeekDraft: {
@@ -108,7 +111,7 @@ ${JSON.stringify(courseContent, null, 4)}
       const parsed: CourseContentDraft = JSON.parse(cleaned);
 
       const courseId = course!.id;
-      await invoke("update_course", { courseId, draft: parsed });
+      await invoke("update_course", { courseId, draft: parsed, origin: "llm" });
       
       setPrompt("");
 
@@ -148,7 +151,7 @@ ${JSON.stringify(courseContent, null, 4)}
                       status,
                     });
 
-                    await invoke<Course>("get_course", { courseId: id }).then((data) => setCourse(data));
+                    await invoke<Course>("get_course", { courseId: course.id }).then((data) => setCourse(data));
                   }}
                   className={`
                     px-4 py-2 text-sm capitalize cursor-pointer transition
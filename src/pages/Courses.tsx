@@ -18,7 +18,7 @@ function Courses() {
   });
 
   useEffect(() => {
-    invoke<CoursePreview[]>("get_courses").then(setCourses);
+    invoke<CoursePage>("get_courses").then((page) => setCourses(page.courses));
   }, []);
 
   const statusOrder: Record<CoursePreview["status"], number> = {
@@ -69,7 +69,7 @@ function Courses() {
 
   async function deleteCourse(courseId: string) {
     await invoke("delete_course", { courseId });
-    invoke<CoursePreview[]>("get_courses").then(setCourses);
+    invoke<CoursePage>("get_courses").then((page) => setCourses(page.courses));
   }
 
   const statusColorMap = new Map();
@@ -79,7 +79,7 @@ function Courses() {
   statusColorMap.set("complete", "#09b04c");
 
   useEffect(() => {
-    invoke<CoursePreview[]>("get_courses").then((data) => setCourses(data));
+    invoke<CoursePage>("get_courses").then((page) => setCourses(page.courses));
   }, []);
 
   return (
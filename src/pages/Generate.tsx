@@ -12,7 +12,9 @@ function Generate() {
   const [courses, setCourses] = useState<CourseDraft[]>([]);
 
   useEffect(() => {
-    invoke<DepartmentDraft[]>("get_departments").then((data) => setDepartments(data));
+    invoke<Department[]>("get_departments").then((data) =>
+      setDepartments(data.map(({ code, name }) => ({ code, name })))
+    );
   }, []);
 
   async function enhance() {
@@ -39,9 +41,12 @@ CourseDraft: {
   name: text,
   description: text,
   book: text,
-  prompt: text
+  prompt: text,
+  level: "intro" | "intermediate" | "advanced" | "graduate"
 }
 
+The level field reflects how much prior study within the department the course assumes.
+
 Each course must have an associated department.
 The department field holds the department code.
 If a course belongs to a pre-existing department, tag it with its code.
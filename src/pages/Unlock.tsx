@@ -0,0 +1,43 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+function Unlock({ onUnlock }: { onUnlock: () => void }) {
+  const [passphrase, setPassphrase] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  async function unlock() {
+    try {
+      await invoke("unlock_database", { passphrase });
+      onUnlock();
+    } catch (err) {
+      setError((err as AppError).message);
+    }
+  }
+
+  return (
+    <div className="max-w-md mx-auto mt-24">
+      <h1 className="text-xl my-4">Unlock</h1>
+      <label className="block mb-2 text-sm font-medium text-neutral-700">
+        Passphrase
+      </label>
+      <input
+        type="password"
+        className="bg-[#f4f5f6] w-full p-3 rounded-xl outline-none mb-3"
+        value={passphrase}
+        onChange={(e) => setPassphrase(e.target.value)}
+        onKeyDown={(e) => e.key === "Enter" && unlock()}
+        placeholder="Enter your passphrase"
+      />
+      <button onClick={unlock} className="button-primary">
+        Unlock
+      </button>
+      {error && (
+        <span className="ml-3 text-sm text-neutral-500">
+          {error}
+        </span>
+      )}
+    </div>
+  );
+}
+
+export default Unlock;
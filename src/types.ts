@@ -12,7 +12,8 @@ type Department = {
 type TargetDraft = {
   serial: number,
   text: string,
-  source: string
+  source: string,
+  estimated_minutes: number | null
 }
 
 type Target = {
@@ -20,7 +21,9 @@ type Target = {
   serial: number,
   text: string,
   source: string,
-  isComplete: boolean
+  isComplete: boolean,
+  estimatedMinutes: number | null,
+  secondsSpent: number
 }
 
 type WeekDraft = {
@@ -35,15 +38,21 @@ type Week = {
   text: string,
   date: string | null,
   isComplete: boolean,
+  estimatedMinutes: number | null,
+  secondsSpent: number,
   targets: Target[]
 }
 
+type CourseLevel = "intro" | "intermediate" | "advanced" | "graduate"
+
 type CourseDraft = {
   department: string,
   name: string,
   description: string,
   book: string,
-  prompt: string
+  prompt: string,
+  level: CourseLevel | null,
+  serial?: number | null
 }
 
 type CourseContentDraft = {
@@ -61,11 +70,16 @@ type Course = {
   description: string,
   book: string,
   prompt: string,
+  level: CourseLevel | null,
   status: string,
+  priority: number,
+  termId: string | null,
+  estimatedMinutes: number | null,
+  secondsSpent: number,
   weeks: Week[]
 }
 
-type CoursePreview = {
+type CourseHeader = {
   id: string,
   department: string,
   serial: number,
@@ -73,7 +87,93 @@ type CoursePreview = {
   status: string
 }
 
+type Progress = {
+  numComplete: number,
+  numTotal: number
+}
+
+type CoursePreview = CourseHeader & {
+  level: CourseLevel | null,
+  priority: number,
+  termId: string | null,
+  weeks: Progress,
+  targets: Progress,
+  nextDate: string | null,
+  numOverdueWeeks: number,
+  lastActivityAt: string | null
+}
+
+type CourseSort = "code" | "priority" | "name" | "progress" | "nextDate" | "lastActivity"
+
+type CourseQuery = {
+  statuses?: string[],
+  departments?: string[],
+  tags?: string[],
+  terms?: string[],
+  text?: string,
+  hasOverdue?: boolean,
+  sort?: CourseSort,
+  descending?: boolean,
+  limit?: number,
+  cursor?: string | null
+}
+
+type CoursePage = {
+  courses: CoursePreview[],
+  nextCursor: string | null
+}
+
+type Tag = {
+  id: string,
+  name: string,
+  color: string
+}
+
+type TaggedItem = {
+  type: "course" | "week" | "target",
+  id: string
+}
+
+type TermDraft = {
+  name: string,
+  startDate: string,
+  endDate: string,
+  breakWeeks: string[]
+}
+
+type Term = {
+  id: string,
+  name: string,
+  startDate: string,
+  endDate: string,
+  breakWeeks: string[]
+}
+
+type TermOverview = {
+  term: Term,
+  numWeeks: number,
+  currentWeek: number | null,
+  courses: CoursePreview[]
+}
+
+type TermOverflow = {
+  term: string,
+  endDate: string,
+  numWeeks: number,
+  lastDate: string
+}
+
 type ScheduleItem = {
-  course: CoursePreview,
+  course: CourseHeader,
   weeks: Week[]
 }
+
+type AppError = {
+  code: "notFound" | "validation" | "conflict" | "database" | "llm" | "io",
+  message: string
+}
+
+type BackgroundError = {
+  task: "backup" | "purgeTrash" | "reminders",
+  error: AppError
+}